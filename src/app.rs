@@ -1,20 +1,25 @@
 use std::cell::Cell;
-use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use gtk4::{
-    prelude::{BoxExt, Cast, CastNone, EventControllerExt, GtkWindowExt, ObjectExt, WidgetExt},
+    gdk::{
+        self,
+        prelude::{DisplayExt, MonitorExt},
+    },
+    glib::object::CastNone,
+    prelude::{BoxExt, EventControllerExt, GtkWindowExt, ListModelExt, ObjectExt, WidgetExt},
     Application, ApplicationWindow, Box as GtkBox, EventControllerMotion, GestureClick, GridView,
     Orientation, PropagationPhase,
 };
-use gtk4_layer_shell::{KeyboardMode, Layer, LayerShell};
+use gtk4_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
 
-use crate::{keybinds, ui};
+use crate::{config::MonitorConfig, hyprland, keybinds, ui};
 
-const APP_NAME: &str = "hyprbucket";
+mod appearance;
+pub mod styles;
 
-pub fn build_ui(app: &Application) {
-    load_styles();
+pub fn build_ui(app: &Application, monitor: Option<&str>) {
+    styles::install();
 
     let window = ApplicationWindow::builder()
         .application(app)
@@ -23,6 +28,7 @@ pub fn build_ui(app: &Application) {
         .build();
 
     setup_layer_shell(&window);
+    let monitor = place_on_monitor(&window, monitor);
 
     let wrapper = GtkBox::new(Orientation::Horizontal, 0);
     wrapper.set_halign(gtk4::Align::Center);
@@ -36,12 +42,20 @@ pub fn build_ui(app: &Application) {
     wrapper.append(&content);
     window.set_child(Some(&wrapper));
 
-    let grid_view = find_grid_view(&content);
+    if let Some(overrides) = monitor
+        .as_ref()
+        .and_then(|name| ui_controller.config().monitors.get(name))
+    {
+        apply_monitor_config(&window, &content, overrides);
+    }
+
+    let grid_view = ui_controller.grid_view();
 
     setup_click_to_close(&window);
     setup_mouse_motion_tracking(&window, grid_view.as_ref());
-    keybinds::setup_keybinds(&window, grid_view.as_ref(), Some(ui_controller));
+    keybinds::setup_keybinds(&window, grid_view.as_ref(), Some(ui_controller.clone()));
 
+    styles::attach(&window, &ui_controller);
     window.present();
 }
 
@@ -53,18 +67,43 @@ fn setup_layer_shell(window: &ApplicationWindow) {
     window.set_exclusive_zone(-1);
 }
 
-fn find_grid_view(container: &GtkBox) -> Option<gtk4::GridView> {
-    let mut child = container.first_child();
+/// Puts the window on `requested`, else on Hyprland's focused monitor, and
+/// returns the connector name of the monitor it was put on. Without either
+/// the compositor picks the output.
+fn place_on_monitor(window: &ApplicationWindow, requested: Option<&str>) -> Option<String> {
+    let name = requested
+        .map(str::to_string)
+        .or_else(hyprland::focused_monitor)?;
+
+    let Some(monitor) = find_monitor(&name) else {
+        eprintln!("Monitor {} not found, letting the compositor choose", name);
+        return None;
+    };
+    window.set_monitor(Some(&monitor));
+    Some(name)
+}
 
-    while let Some(widget) = child {
-        if let Some(scrolled) = widget.downcast_ref::<gtk4::ScrolledWindow>() {
-            if let Some(grid_view) = scrolled.child().and_downcast::<gtk4::GridView>() {
-                return Some(grid_view);
-            }
-        }
-        child = widget.next_sibling();
+fn find_monitor(connector: &str) -> Option<gdk::Monitor> {
+    let monitors = gdk::Display::default()?.monitors();
+    (0..monitors.n_items())
+        .filter_map(|position| monitors.item(position).and_downcast::<gdk::Monitor>())
+        .find(|monitor| monitor.connector().is_some_and(|name| name == connector))
+}
+
+fn apply_monitor_config(window: &ApplicationWindow, content: &GtkBox, config: &MonitorConfig) {
+    if config.width.is_some() || config.height.is_some() {
+        content.set_size_request(config.width.unwrap_or(-1), config.height.unwrap_or(-1));
+    }
+
+    // Anchoring to one edge only keeps the window centered along the other axis.
+    if let Some(x) = config.x {
+        window.set_anchor(Edge::Left, true);
+        window.set_margin(Edge::Left, x);
+    }
+    if let Some(y) = config.y {
+        window.set_anchor(Edge::Top, true);
+        window.set_margin(Edge::Top, y);
     }
-    None
 }
 
 fn setup_click_to_close(window: &ApplicationWindow) {
@@ -79,43 +118,6 @@ fn setup_click_to_close(window: &ApplicationWindow) {
     window.add_controller(click);
 }
 
-fn load_styles() {
-    let provider = gtk4::CssProvider::new();
-    let mut css_loaded = false;
-
-    if let Ok(home) = std::env::var("HOME") {
-        let user_css: PathBuf = PathBuf::from(home)
-            .join(".config")
-            .join(APP_NAME)
-            .join("default.css");
-
-        if user_css.exists() {
-            provider.load_from_path(&user_css);
-            css_loaded = true;
-        }
-    }
-
-    if !css_loaded {
-        let default_css = Path::new("resources/default.css");
-        if default_css.exists() {
-            provider.load_from_path(default_css);
-            css_loaded = true;
-        }
-    }
-
-    if !css_loaded {
-        provider.load_from_bytes(&gtk4::glib::Bytes::from_static(include_bytes!(
-            "../resources/default.css"
-        )));
-    }
-
-    gtk4::style_context_add_provider_for_display(
-        &gtk4::gdk::Display::default().expect("Could not get default display"),
-        &provider,
-        gtk4::STYLE_PROVIDER_PRIORITY_USER,
-    );
-}
-
 fn setup_mouse_motion_tracking(window: &ApplicationWindow, grid_view: Option<&GridView>) {
     let Some(grid_view) = grid_view else {
         return;
@@ -0,0 +1,222 @@
+use std::{cell::Cell, rc::Rc};
+
+use gtk4::{
+    gio::{self, DBusCallFlags, DBusConnection, DBusSignalFlags},
+    glib::{self, prelude::ToVariant},
+    prelude::ObjectExt,
+};
+
+use crate::launcher::dbus::session_bus;
+
+const PORTAL_BUS: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+/// Read synchronously before the first frame, so keep it short.
+const READ_TIMEOUT_MS: i32 = 300;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    pub fn css_class(self) -> &'static str {
+        match self {
+            ColorScheme::Dark => "dark",
+            ColorScheme::Light => "light",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Appearance {
+    pub scheme: ColorScheme,
+    /// Accent color as RGB in 0..=1, `None` when the system has none.
+    pub accent: Option<(f64, f64, f64)>,
+}
+
+impl Appearance {
+    pub fn accent_css(&self) -> String {
+        let Some((r, g, b)) = self.accent else {
+            return String::new();
+        };
+        let channel = |value: f64| (value * 255.0).round() as u8;
+        format!(
+            "@define-color accent_color rgb({}, {}, {});\n",
+            channel(r),
+            channel(g),
+            channel(b)
+        )
+    }
+}
+
+pub struct AppearanceWatch {
+    _subscription: Option<gio::SignalSubscription>,
+    _gtk_handlers: Vec<glib::SignalHandlerId>,
+}
+
+/// Reads the current appearance and calls `on_change` whenever it changes.
+/// The XDG settings portal is preferred, GTK settings are the fallback when
+/// it is not available.
+pub fn watch<F>(on_change: F) -> (Appearance, AppearanceWatch)
+where
+    F: Fn(Appearance) + 'static,
+{
+    let connection = session_bus();
+    let portal = connection.as_ref().and_then(read_portal);
+
+    let Some((connection, appearance)) = connection.zip(portal) else {
+        let appearance = from_gtk_settings();
+        return (
+            appearance,
+            AppearanceWatch {
+                _subscription: None,
+                _gtk_handlers: watch_gtk_settings(on_change),
+            },
+        );
+    };
+
+    let current = Rc::new(Cell::new(appearance));
+    let subscription = connection.subscribe_to_signal(
+        Some(PORTAL_BUS),
+        Some(SETTINGS_INTERFACE),
+        Some("SettingChanged"),
+        Some(PORTAL_PATH),
+        Some(APPEARANCE_NAMESPACE),
+        DBusSignalFlags::NONE,
+        move |signal| {
+            let Some((namespace, key, value)) =
+                signal.parameters.get::<(String, String, glib::Variant)>()
+            else {
+                return;
+            };
+            if namespace != APPEARANCE_NAMESPACE {
+                return;
+            }
+
+            let mut appearance = current.get();
+            match key.as_str() {
+                "color-scheme" => appearance.scheme = parse_scheme(&value),
+                "accent-color" => appearance.accent = parse_accent(&value),
+                _ => return,
+            }
+            if appearance != current.get() {
+                current.set(appearance);
+                on_change(appearance);
+            }
+        },
+    );
+
+    (
+        appearance,
+        AppearanceWatch {
+            _subscription: Some(subscription),
+            _gtk_handlers: Vec::new(),
+        },
+    )
+}
+
+fn read_portal(connection: &DBusConnection) -> Option<Appearance> {
+    let scheme = read_setting(connection, "color-scheme")?;
+    let accent = read_setting(connection, "accent-color");
+
+    Some(Appearance {
+        scheme: parse_scheme(&scheme),
+        accent: accent.as_ref().and_then(parse_accent),
+    })
+}
+
+fn read_setting(connection: &DBusConnection, key: &str) -> Option<glib::Variant> {
+    // `ReadOne` is only in version 2 of the interface, `Read` wraps the
+    // value in an extra variant.
+    for method in ["ReadOne", "Read"] {
+        let result = connection.call_sync(
+            Some(PORTAL_BUS),
+            PORTAL_PATH,
+            SETTINGS_INTERFACE,
+            method,
+            Some(&(APPEARANCE_NAMESPACE, key).to_variant()),
+            None,
+            // Starting the portal here would hold up the first frame.
+            DBusCallFlags::NO_AUTO_START,
+            READ_TIMEOUT_MS,
+            gio::Cancellable::NONE,
+        );
+
+        match result {
+            Ok(reply) => {
+                let mut value = reply.child_value(0);
+                while let Some(inner) = value.as_variant() {
+                    value = inner;
+                }
+                return Some(value);
+            }
+            Err(e) if e.matches(gio::DBusError::UnknownMethod) => continue,
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// 1 prefers dark and 2 light. Without a preference the launcher keeps its
+/// dark default.
+fn parse_scheme(value: &glib::Variant) -> ColorScheme {
+    match value.get::<u32>() {
+        Some(2) => ColorScheme::Light,
+        _ => ColorScheme::Dark,
+    }
+}
+
+/// Channels outside 0..=1 mean the accent color is unset.
+fn parse_accent(value: &glib::Variant) -> Option<(f64, f64, f64)> {
+    let (r, g, b) = value.get::<(f64, f64, f64)>()?;
+    [r, g, b]
+        .iter()
+        .all(|channel| (0.0..=1.0).contains(channel))
+        .then_some((r, g, b))
+}
+
+fn from_gtk_settings() -> Appearance {
+    let Some(settings) = gtk4::Settings::default() else {
+        return Appearance::default();
+    };
+
+    let theme_name = settings
+        .gtk_theme_name()
+        .map(|name| name.to_lowercase())
+        .unwrap_or_default();
+    // Like the portal's "no preference", an unconfigured GTK keeps the dark
+    // default, only a light theme name switches it.
+    let light = !settings.is_gtk_application_prefer_dark_theme() && theme_name.contains("light");
+
+    Appearance {
+        scheme: if light {
+            ColorScheme::Light
+        } else {
+            ColorScheme::Dark
+        },
+        accent: None,
+    }
+}
+
+fn watch_gtk_settings<F>(on_change: F) -> Vec<glib::SignalHandlerId>
+where
+    F: Fn(Appearance) + 'static,
+{
+    let Some(settings) = gtk4::Settings::default() else {
+        return Vec::new();
+    };
+
+    let on_change = Rc::new(on_change);
+    ["gtk-application-prefer-dark-theme", "gtk-theme-name"]
+        .into_iter()
+        .map(|property| {
+            let on_change = on_change.clone();
+            settings
+                .connect_notify_local(Some(property), move |_, _| on_change(from_gtk_settings()))
+        })
+        .collect()
+}
@@ -0,0 +1,360 @@
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+use gtk4::{
+    gdk,
+    gio::{self, prelude::*},
+    glib,
+    prelude::WidgetExt,
+    CssProvider, CssSection, Widget,
+};
+
+use super::appearance::{self, Appearance, AppearanceWatch, ColorScheme};
+use crate::{config::Config, ui::UiController};
+
+pub const DEFAULT_THEME: &str = "default";
+
+const DEFAULT_CSS: &str = include_str!("../../resources/default.css");
+const LIGHT_CSS: &str = include_str!("../../resources/themes/light.css");
+
+const BUNDLED_THEMES: [(&str, &str); 4] = [
+    ("dark", ""),
+    ("light", LIGHT_CSS),
+    ("solid", include_str!("../../resources/themes/solid.css")),
+    (
+        "compact",
+        include_str!("../../resources/themes/compact.css"),
+    ),
+];
+
+/// The process-wide stylesheets, kept across launcher windows so they are
+/// only added to the display once.
+struct Styles {
+    theme: CssProvider,
+    legacy: CssProvider,
+    accent: CssProvider,
+    user: CssProvider,
+    theme_name: String,
+    appearance: Appearance,
+    _appearance_watch: AppearanceWatch,
+    // Dropping a monitor stops it.
+    _monitors: Vec<gio::FileMonitor>,
+    errors: Vec<String>,
+    ui: Option<UiController>,
+    showing_errors: bool,
+}
+
+enum ThemeSource {
+    File(PathBuf),
+    Bundled(String),
+}
+
+thread_local! {
+    static STYLES: RefCell<Option<Styles>> = const { RefCell::new(None) };
+    static THEME_OVERRIDE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn set_theme(name: &str) {
+    THEME_OVERRIDE.with_borrow_mut(|theme| *theme = Some(name.to_string()));
+}
+
+pub fn install() {
+    if STYLES.with_borrow(Option::is_some) {
+        return;
+    }
+
+    let display = gdk::Display::default().expect("Could not get default display");
+    let theme = CssProvider::new();
+    let legacy = CssProvider::new();
+    let accent = CssProvider::new();
+    let user = CssProvider::new();
+    for (provider, priority) in [
+        (&theme, gtk4::STYLE_PROVIDER_PRIORITY_USER),
+        (&legacy, gtk4::STYLE_PROVIDER_PRIORITY_USER + 1),
+        (&accent, gtk4::STYLE_PROVIDER_PRIORITY_USER + 2),
+        (&user, gtk4::STYLE_PROVIDER_PRIORITY_USER + 3),
+    ] {
+        provider.connect_parsing_error(|_, section, error| {
+            push_error(describe_error(section, error));
+        });
+        gtk4::style_context_add_provider_for_display(&display, provider, priority);
+    }
+
+    let (appearance, appearance_watch) = appearance::watch(set_appearance);
+
+    STYLES.with_borrow_mut(|styles| {
+        *styles = Some(Styles {
+            theme,
+            legacy,
+            accent,
+            user,
+            theme_name: String::new(),
+            appearance,
+            _appearance_watch: appearance_watch,
+            _monitors: watch(),
+            errors: Vec::new(),
+            ui: None,
+            showing_errors: false,
+        })
+    });
+    reload();
+}
+
+pub fn attach(window: &impl IsA<Widget>, ui: &UiController) {
+    let scheme = STYLES.with_borrow_mut(|styles| {
+        styles.as_mut().map(|styles| {
+            styles.ui = Some(ui.clone());
+            styles.showing_errors = false;
+            styles.appearance.scheme
+        })
+    });
+    set_scheme_class(window.as_ref(), scheme.unwrap_or_default());
+
+    // The banner only shows up once the window is mapped.
+    glib::idle_add_local_once(report_errors);
+}
+
+pub fn reload() {
+    let providers = STYLES.with_borrow_mut(|styles| {
+        styles.as_mut().map(|styles| {
+            styles.errors.clear();
+            (
+                styles.theme.clone(),
+                styles.legacy.clone(),
+                styles.accent.clone(),
+                styles.user.clone(),
+                styles.appearance,
+            )
+        })
+    });
+    let Some((theme, legacy, accent, user, appearance)) = providers else {
+        return;
+    };
+
+    let name = selected_theme();
+    let source = theme_source(&name, appearance.scheme);
+
+    // Earlier versions were customized by copying the whole default stylesheet
+    // to default.css, so it only has the rules of its time and goes on top of
+    // the bundled default instead of replacing it.
+    let legacy_path = config_dir()
+        .map(|dir| dir.join("default.css"))
+        .filter(|path| {
+            name == DEFAULT_THEME
+                && matches!(source, Some(ThemeSource::Bundled(_)))
+                && path.exists()
+        });
+
+    // Parsing errors are reported synchronously while loading.
+    match source {
+        Some(ThemeSource::File(path)) => theme.load_from_path(&path),
+        Some(ThemeSource::Bundled(css)) => theme.load_from_string(&css),
+        None => {
+            push_error(format!(
+                "Unknown theme \"{}\", using the default theme",
+                name
+            ));
+            theme.load_from_string(DEFAULT_CSS);
+        }
+    }
+
+    match legacy_path {
+        Some(path) => legacy.load_from_path(&path),
+        None => legacy.load_from_string(""),
+    }
+
+    accent.load_from_string(&appearance.accent_css());
+
+    match config_dir().map(|dir| dir.join("style.css")) {
+        Some(path) if path.exists() => user.load_from_path(&path),
+        _ => user.load_from_string(""),
+    }
+
+    STYLES.with_borrow_mut(|styles| {
+        if let Some(styles) = styles {
+            styles.theme_name = name;
+        }
+    });
+    report_errors();
+}
+
+fn selected_theme() -> String {
+    THEME_OVERRIDE
+        .with_borrow(Clone::clone)
+        .or_else(|| Config::try_load().map(|config| config.theme))
+        .unwrap_or_else(|| DEFAULT_THEME.to_string())
+}
+
+fn set_appearance(appearance: Appearance) {
+    STYLES.with_borrow_mut(|styles| {
+        if let Some(styles) = styles {
+            styles.appearance = appearance;
+        }
+    });
+    reload();
+
+    for window in gtk4::Window::list_toplevels() {
+        set_scheme_class(&window, appearance.scheme);
+    }
+}
+
+fn set_scheme_class(widget: &Widget, scheme: ColorScheme) {
+    for other in [ColorScheme::Dark, ColorScheme::Light] {
+        if other != scheme {
+            widget.remove_css_class(other.css_class());
+        }
+    }
+    widget.add_css_class(scheme.css_class());
+}
+
+/// `themes/<name>.css` in the config directory, else a bundled theme of
+/// that name. The default theme follows the system color scheme.
+fn theme_source(name: &str, scheme: ColorScheme) -> Option<ThemeSource> {
+    if let Some(path) = config_dir()
+        .map(|dir| dir.join("themes").join(format!("{}.css", name)))
+        .filter(|path| path.exists())
+    {
+        return Some(ThemeSource::File(path));
+    }
+
+    if name == DEFAULT_THEME {
+        return Some(ThemeSource::Bundled(match scheme {
+            ColorScheme::Dark => DEFAULT_CSS.to_string(),
+            ColorScheme::Light => format!("{}\n{}", DEFAULT_CSS, LIGHT_CSS),
+        }));
+    }
+
+    BUNDLED_THEMES
+        .iter()
+        .find(|(bundled, _)| *bundled == name)
+        .map(|(_, css)| ThemeSource::Bundled(format!("{}\n{}", DEFAULT_CSS, css)))
+}
+
+fn push_error(message: String) {
+    eprintln!("Stylesheet error: {}", message);
+    STYLES.with_borrow_mut(|styles| {
+        if let Some(styles) = styles {
+            styles.errors.push(message);
+        }
+    });
+}
+
+fn report_errors() {
+    let state = STYLES.with_borrow(|styles| {
+        let styles = styles.as_ref()?;
+        Some((
+            styles.ui.clone()?,
+            styles.errors.clone(),
+            styles.showing_errors,
+        ))
+    });
+    let Some((ui, errors, showing_errors)) = state else {
+        return;
+    };
+
+    let showing = match errors.as_slice() {
+        [] => {
+            if showing_errors {
+                ui.hide_error();
+            }
+            false
+        }
+        [error] => ui.show_error("Stylesheet error", error),
+        [error, rest @ ..] => ui.show_error(
+            "Stylesheet errors",
+            &format!("{}\n…and {} more, see stderr", error, rest.len()),
+        ),
+    };
+
+    STYLES.with_borrow_mut(|styles| {
+        if let Some(styles) = styles {
+            styles.showing_errors = showing;
+        }
+    });
+}
+
+fn describe_error(section: &CssSection, error: &glib::Error) -> String {
+    let file = section
+        .file()
+        .and_then(|file| file.path())
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "built-in theme".to_string());
+    let location = section.start_location();
+
+    format!(
+        "{}:{}:{}: {}",
+        file,
+        location.lines() + 1,
+        location.line_chars() + 1,
+        error.message()
+    )
+}
+
+fn config_dir() -> Option<PathBuf> {
+    Config::path().parent().map(Path::to_path_buf)
+}
+
+/// Watches the stylesheets, including ones that do not exist yet so creating
+/// them takes effect right away, and config.toml for a change of theme.
+fn watch() -> Vec<gio::FileMonitor> {
+    let Some(dir) = config_dir() else {
+        return Vec::new();
+    };
+
+    let mut monitors: Vec<gio::FileMonitor> = [
+        (dir.join("style.css"), false),
+        (dir.join("default.css"), false),
+        (dir.join("themes"), true),
+    ]
+    .iter()
+    .filter_map(|(path, directory)| {
+        let monitor = monitor(path, *directory)?;
+        monitor.connect_changed(|_, _, _, event| {
+            if matches!(
+                event,
+                gio::FileMonitorEvent::ChangesDoneHint
+                    | gio::FileMonitorEvent::Created
+                    | gio::FileMonitorEvent::Deleted
+            ) {
+                reload();
+            }
+        });
+        Some(monitor)
+    })
+    .collect();
+
+    if let Some(monitor) = monitor(&Config::path(), false) {
+        monitor.connect_changed(|_, _, _, event| {
+            if event != gio::FileMonitorEvent::ChangesDoneHint {
+                return;
+            }
+            // config.toml is also written when pinning, which should not restyle.
+            let current = STYLES.with_borrow(|styles| {
+                styles
+                    .as_ref()
+                    .map(|styles| styles.theme_name.clone())
+                    .unwrap_or_default()
+            });
+            if selected_theme() != current {
+                reload();
+            }
+        });
+        monitors.push(monitor);
+    }
+
+    monitors
+}
+
+fn monitor(path: &Path, directory: bool) -> Option<gio::FileMonitor> {
+    let file = gio::File::for_path(path);
+    let monitor = if directory {
+        file.monitor_directory(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+    } else {
+        file.monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE)
+    };
+    monitor
+        .inspect_err(|e| eprintln!("Failed to watch {}: {}", path.display(), e))
+        .ok()
+}
@@ -1,28 +1,261 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use toml_edit::{value, Array, DocumentMut, Item, Table, TomlError};
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+use crate::{
+    app::styles::DEFAULT_THEME,
+    desktop::{DesktopEntry, CUSTOM_ID_PREFIX},
+    keybinds::{CloseToggle, QuickLaunch},
+    launcher::backend::LaunchBackend,
+    ui::components::{Layout, Subtitle},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub pinned: Vec<String>,
+    pub pin_groups: BTreeMap<String, String>,
+    pub hidden: Vec<String>,
+    pub focus_running: bool,
+    pub launch_backend: LaunchBackend,
+    pub hyprland_exec_rules: Option<String>,
+    pub terminal: Option<String>,
+    pub terminal_args: Option<Vec<String>>,
+    pub launch_grace_ms: u64,
+    pub launch_error_notifications: bool,
+    pub close_on_launch: bool,
+    pub close_toggle: CloseToggle,
+    pub theme: String,
+    pub layout: Layout,
+    pub subtitle: Subtitle,
+    pub show_categories: bool,
+    pub show_details: bool,
+    pub grid: GridConfig,
+    pub quick_launch: QuickLaunch,
+    pub wrap_navigation: bool,
+    pub emacs_navigation: bool,
+    pub vim_mode: bool,
+    pub monitors: BTreeMap<String, MonitorConfig>,
+    pub apps: BTreeMap<String, AppConfig>,
+    pub entries: Vec<CustomEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GridConfig {
+    pub columns: u32,
+    pub tile_size: i32,
+    pub icon_size: i32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            columns: 5,
+            tile_size: 96,
+            icon_size: 48,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MonitorConfig {
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub aliases: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub args: Vec<String>,
+    pub terminal: Option<bool>,
+    pub launch_backend: Option<LaunchBackend>,
+}
+
+impl AppConfig {
+    pub fn apply(&self, entry: &mut DesktopEntry) {
+        if let Some(name) = &self.name {
+            entry.name = name.clone();
+        }
+        if let Some(icon) = &self.icon {
+            entry.icon = Some(icon.clone());
+        }
+        entry.keywords.extend(self.aliases.iter().cloned());
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CustomEntry {
+    /// Stable id used for pinning, derived from the name when left out.
+    pub id: Option<String>,
+    pub name: String,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    pub exec: String,
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    pub terminal: bool,
+}
+
+impl CustomEntry {
+    pub fn to_desktop_entry(&self) -> Option<DesktopEntry> {
+        if self.name.trim().is_empty() || self.exec.trim().is_empty() {
+            return None;
+        }
+
+        let slug = match &self.id {
+            Some(id) => id.clone(),
+            None => self
+                .name
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("-"),
+        };
+
+        Some(DesktopEntry {
+            id: format!("{}{}", CUSTOM_ID_PREFIX, slug),
+            name: self.name.clone(),
+            exec: self.exec.clone(),
+            icon: self.icon.clone(),
+            comment: self.comment.clone(),
+            terminal: self.terminal,
+            keywords: self.keywords.clone(),
+            categories: self.categories.clone(),
+            source_path: Some(Config::path()),
+            ..DesktopEntry::default()
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            pinned: Vec::new(),
+            pin_groups: BTreeMap::new(),
+            hidden: Vec::new(),
+            focus_running: false,
+            launch_backend: LaunchBackend::default(),
+            hyprland_exec_rules: None,
+            terminal: None,
+            terminal_args: None,
+            launch_grace_ms: 1500,
+            launch_error_notifications: true,
+            close_on_launch: true,
+            close_toggle: CloseToggle::default(),
+            theme: DEFAULT_THEME.to_string(),
+            layout: Layout::default(),
+            subtitle: Subtitle::default(),
+            show_categories: true,
+            show_details: false,
+            grid: GridConfig::default(),
+            quick_launch: QuickLaunch::default(),
+            wrap_navigation: false,
+            emacs_navigation: false,
+            vim_mode: false,
+            monitors: BTreeMap::new(),
+            apps: BTreeMap::new(),
+            entries: Vec::new(),
+        }
+    }
 }
 
 impl Config {
     pub fn load() -> Self {
-        let path = Self::config_path();
+        let path = Self::path();
 
         if path.exists() {
-            let content = fs::read_to_string(&path).unwrap_or_default();
-            toml::from_str(&content).unwrap_or_default()
+            Self::try_load().unwrap_or_default()
         } else {
             Self::create_default()
         }
     }
 
+    pub fn try_load() -> Option<Self> {
+        let content = fs::read_to_string(Self::path()).ok()?;
+        match toml::from_str(&content) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("Failed to parse config.toml: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Loads the config for a read-modify-write, refusing when the existing
+    /// file does not parse so it is not overwritten with defaults.
+    pub fn load_for_update() -> Option<Self> {
+        if Self::path().exists() {
+            Self::try_load()
+        } else {
+            Some(Self::default())
+        }
+    }
+
+    /// Writes the state the launcher manages itself into config.toml. Only
+    /// `pinned`, `pin_groups` and `hidden` are touched so comments, key order
+    /// and unset defaults in the hand-written file survive.
     pub fn save(&self) {
-        let path = Self::config_path();
-        let content = toml::to_string(self).unwrap();
-        fs::write(path, content).unwrap();
+        let path = Self::path();
+        let content = fs::read_to_string(&path).unwrap_or_default();
+
+        match self.merge_into(&content) {
+            Ok(content) => {
+                if let Err(e) = fs::write(&path, content) {
+                    eprintln!("Failed to write config.toml: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Not saving, failed to parse config.toml: {}", e),
+        }
+    }
+
+    fn merge_into(&self, content: &str) -> Result<String, TomlError> {
+        let mut doc = content.parse::<DocumentMut>()?;
+
+        set_ids(&mut doc, "pinned", &self.pinned);
+
+        if !self.hidden.is_empty() || doc.contains_key("hidden") {
+            set_ids(&mut doc, "hidden", &self.hidden);
+        }
+
+        if !self.pin_groups.is_empty() || doc.contains_key("pin_groups") {
+            if !doc.get("pin_groups").is_some_and(Item::is_table) {
+                doc["pin_groups"] = Item::Table(Table::new());
+            }
+            if let Some(groups) = doc["pin_groups"].as_table_mut() {
+                groups.clear();
+                for (id, group) in &self.pin_groups {
+                    groups.insert(id, value(group.as_str()));
+                }
+            }
+        }
+
+        Ok(doc.to_string())
+    }
+
+    pub fn app(&self, app_id: &str) -> Option<&AppConfig> {
+        self.apps.get(app_id).or_else(|| {
+            app_id
+                .strip_suffix(".desktop")
+                .and_then(|stem| self.apps.get(stem))
+        })
+    }
+
+    pub fn custom_entries(&self) -> Vec<DesktopEntry> {
+        self.entries
+            .iter()
+            .filter_map(CustomEntry::to_desktop_entry)
+            .collect()
     }
 
     pub fn toggle_pin(&mut self, app_id: &str) {
@@ -34,7 +267,7 @@ impl Config {
         self.save();
     }
 
-    fn config_path() -> PathBuf {
+    pub fn path() -> PathBuf {
         let config_dir = PathBuf::from(std::env::var("HOME").unwrap())
             .join(".config")
             .join("hyprbucket");
@@ -49,3 +282,83 @@ impl Config {
         config
     }
 }
+
+fn set_ids(doc: &mut DocumentMut, key: &str, ids: &[String]) {
+    let mut array: Array = ids.iter().map(String::as_str).collect();
+    if let Some(old) = doc.get(key).and_then(Item::as_value) {
+        *array.decor_mut() = old.decor().clone();
+    }
+    doc[key] = value(array);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAND_WRITTEN: &str = r#"# Pinned apps
+pinned = ["kitty.desktop"] # terminal first
+
+terminal = "foot"
+
+[pin_groups]
+"code.desktop" = "Work"
+
+[[entries]]
+name = "Wiki"
+exec = "xdg-open https://wiki.hyprland.org"
+"#;
+
+    #[test]
+    fn merge_only_touches_launcher_state() {
+        let config = Config {
+            pinned: vec!["firefox.desktop".to_string(), "kitty.desktop".to_string()],
+            pin_groups: BTreeMap::from([("firefox.desktop".to_string(), "Web".to_string())]),
+            ..Config::default()
+        };
+
+        let merged = config.merge_into(HAND_WRITTEN).unwrap();
+
+        assert_eq!(
+            merged,
+            r#"# Pinned apps
+pinned = ["firefox.desktop", "kitty.desktop"] # terminal first
+
+terminal = "foot"
+
+[pin_groups]
+"firefox.desktop" = "Web"
+
+[[entries]]
+name = "Wiki"
+exec = "xdg-open https://wiki.hyprland.org"
+"#
+        );
+    }
+
+    #[test]
+    fn merge_leaves_defaults_out_of_a_new_file() {
+        let config = Config {
+            hidden: vec!["htop.desktop".to_string()],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.merge_into("").unwrap(),
+            "pinned = []\nhidden = [\"htop.desktop\"]\n"
+        );
+    }
+
+    #[test]
+    fn merge_refuses_an_unparsable_file() {
+        assert!(Config::default().merge_into("pinned = [").is_err());
+    }
+
+    #[test]
+    fn close_toggle_defaults_to_shift() {
+        let config: Config = toml::from_str(HAND_WRITTEN).unwrap();
+        assert_eq!(config.close_toggle, CloseToggle::Shift);
+
+        let config: Config = toml::from_str("close_toggle = \"off\"").unwrap();
+        assert_eq!(config.close_toggle, CloseToggle::Off);
+    }
+}
@@ -8,18 +8,145 @@ use std::{
 use async_channel::Sender;
 use walkdir::WalkDir;
 
-#[derive(Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub const CUSTOM_ID_PREFIX: &str = "custom:";
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct DesktopEntry {
     pub id: String,
     pub name: String,
     pub exec: String,
     pub icon: Option<String>,
     pub terminal: bool,
+    #[serde(default)]
+    pub generic_name: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub startup_wm_class: Option<String>,
+    #[serde(default)]
+    pub startup_notify: Option<bool>,
+    #[serde(default)]
+    pub dbus_activatable: bool,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<DesktopAction>,
+    #[serde(default)]
+    pub source_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct DesktopAction {
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,
+}
+
+/// The main categories of the freedesktop menu specification, with Audio and
+/// Video folded into AudioVideo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MainCategory {
+    AudioVideo,
+    Development,
+    Education,
+    Game,
+    Graphics,
+    Network,
+    Office,
+    Science,
+    Settings,
+    System,
+    Utility,
+}
+
+impl MainCategory {
+    pub const ALL: [MainCategory; 11] = [
+        MainCategory::AudioVideo,
+        MainCategory::Development,
+        MainCategory::Education,
+        MainCategory::Game,
+        MainCategory::Graphics,
+        MainCategory::Network,
+        MainCategory::Office,
+        MainCategory::Science,
+        MainCategory::Settings,
+        MainCategory::System,
+        MainCategory::Utility,
+    ];
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "AudioVideo" | "Audio" | "Video" => MainCategory::AudioVideo,
+            "Development" => MainCategory::Development,
+            "Education" => MainCategory::Education,
+            "Game" => MainCategory::Game,
+            "Graphics" => MainCategory::Graphics,
+            "Network" => MainCategory::Network,
+            "Office" => MainCategory::Office,
+            "Science" => MainCategory::Science,
+            "Settings" => MainCategory::Settings,
+            "System" => MainCategory::System,
+            "Utility" => MainCategory::Utility,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MainCategory::AudioVideo => "Multimedia",
+            MainCategory::Development => "Development",
+            MainCategory::Education => "Education",
+            MainCategory::Game => "Games",
+            MainCategory::Graphics => "Graphics",
+            MainCategory::Network => "Internet",
+            MainCategory::Office => "Office",
+            MainCategory::Science => "Science",
+            MainCategory::Settings => "Settings",
+            MainCategory::System => "System",
+            MainCategory::Utility => "Utilities",
+        }
+    }
+}
+
+impl DesktopEntry {
+    pub fn main_categories(&self) -> impl Iterator<Item = MainCategory> + '_ {
+        self.categories
+            .iter()
+            .filter_map(|category| MainCategory::from_key(category))
+    }
+
+    pub fn in_category(&self, category: MainCategory) -> bool {
+        self.main_categories().any(|main| main == category)
+    }
+
+    /// Whether a Hyprland window class belongs to this entry, using
+    /// `StartupWMClass` when present and the desktop id otherwise.
+    pub fn matches_window_class(&self, class: &str) -> bool {
+        if class.is_empty() {
+            return false;
+        }
+
+        if let Some(wm_class) = &self.startup_wm_class {
+            return wm_class.eq_ignore_ascii_case(class);
+        }
+
+        let id = self.id.strip_suffix(".desktop").unwrap_or(&self.id);
+        id.eq_ignore_ascii_case(class)
+            || id
+                .rsplit('.')
+                .next()
+                .is_some_and(|short| short.eq_ignore_ascii_case(class))
+    }
 }
 
 pub enum LoaderMsg {
     Batch(Vec<DesktopEntry>),
-    App(DesktopEntry),
+    App(Box<DesktopEntry>),
     Remove(Vec<String>),
     Done,
 }
@@ -110,7 +237,7 @@ pub fn spawn_load_entries(sender: Sender<LoaderMsg>, pinned: HashSet<String>) {
                         .unwrap_or(true);
 
                     if changed {
-                        let _ = sender.send_blocking(LoaderMsg::App(desktop_entry));
+                        let _ = sender.send_blocking(LoaderMsg::App(Box::new(desktop_entry)));
                     }
                 }
             }
@@ -158,11 +285,32 @@ fn parse_desktop_file(path: &std::path::Path) -> Option<DesktopEntry> {
     let mut name: Option<String> = None;
     let mut exec: Option<String> = None;
     let mut icon: Option<String> = None;
+    let mut generic_name: Option<String> = None;
+    let mut comment: Option<String> = None;
     let mut terminal = false;
     let mut no_display = false;
+    let mut startup_wm_class: Option<String> = None;
+    let mut startup_notify: Option<bool> = None;
+    let mut dbus_activatable = false;
+    let mut working_dir: Option<String> = None;
+    let mut keywords: Vec<String> = Vec::new();
+    let mut categories: Vec<String> = Vec::new();
+    let mut action_ids: Vec<String> = Vec::new();
+    let mut actions: Vec<DesktopAction> = Vec::new();
+    let mut group = String::new();
 
     for line in content.lines() {
-        if line.starts_with('[') || line.trim().is_empty() {
+        if let Some(header) = line.trim().strip_prefix('[') {
+            group = header.trim_end_matches(']').to_string();
+            if let Some(id) = group.strip_prefix("Desktop Action ") {
+                actions.push(DesktopAction {
+                    id: id.to_string(),
+                    ..DesktopAction::default()
+                });
+            }
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with('#') {
             continue;
         }
 
@@ -173,6 +321,21 @@ fn parse_desktop_file(path: &std::path::Path) -> Option<DesktopEntry> {
         let key = key.trim();
         let value = value.trim();
 
+        if group.starts_with("Desktop Action ") {
+            if let Some(action) = actions.last_mut() {
+                match key {
+                    "Name" if action.name.is_empty() => action.name = value.to_string(),
+                    "Exec" if action.exec.is_empty() => action.exec = value.to_string(),
+                    "Icon" if action.icon.is_none() => action.icon = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        if group != "Desktop Entry" {
+            continue;
+        }
+
         match key {
             "Name" if !key.contains('[') => {
                 if name.is_none() {
@@ -189,12 +352,54 @@ fn parse_desktop_file(path: &std::path::Path) -> Option<DesktopEntry> {
                     icon = Some(value.to_string());
                 }
             }
+            "GenericName" if generic_name.is_none() && !value.is_empty() => {
+                generic_name = Some(value.to_string());
+            }
+            "Comment" if comment.is_none() && !value.is_empty() => {
+                comment = Some(value.to_string());
+            }
             "Terminal" => {
                 terminal = value == "true";
             }
             "NoDisplay" => {
                 no_display = value == "true";
             }
+            "Path" if working_dir.is_none() && !value.is_empty() => {
+                working_dir = Some(value.to_string());
+            }
+            "Keywords" if keywords.is_empty() => {
+                keywords = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "Categories" if categories.is_empty() => {
+                categories = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|category| !category.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "DBusActivatable" => {
+                dbus_activatable = value == "true";
+            }
+            "StartupWMClass" if startup_wm_class.is_none() => {
+                startup_wm_class = Some(value.to_string());
+            }
+            "Actions" if action_ids.is_empty() => {
+                action_ids = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "StartupNotify" if startup_notify.is_none() => {
+                startup_notify = Some(value == "true");
+            }
             _ => {}
         }
     }
@@ -205,11 +410,29 @@ fn parse_desktop_file(path: &std::path::Path) -> Option<DesktopEntry> {
 
     let id = path.file_name()?.to_str()?.to_string();
 
+    // Only groups listed in `Actions=` count, in that order.
+    let actions = action_ids
+        .iter()
+        .filter_map(|id| actions.iter().find(|action| &action.id == id))
+        .filter(|action| !action.name.is_empty() && !action.exec.is_empty())
+        .cloned()
+        .collect();
+
     Some(DesktopEntry {
         id,
         name: name.unwrap(),
         exec: exec.unwrap(),
         icon,
         terminal,
+        generic_name,
+        comment,
+        startup_wm_class,
+        startup_notify,
+        dbus_activatable,
+        working_dir,
+        keywords,
+        categories,
+        actions,
+        source_path: Some(path.to_path_buf()),
     })
 }
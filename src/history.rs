@@ -0,0 +1,66 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+thread_local! {
+    static HISTORY: RefCell<Option<HashMap<String, i64>>> = const { RefCell::new(None) };
+}
+
+/// Reads history.json once, later lookups and launches use the copy in memory.
+pub fn preload() {
+    if HISTORY.with_borrow(Option::is_some) {
+        return;
+    }
+
+    let history = history_path().map(|path| load(&path)).unwrap_or_default();
+    HISTORY.with_borrow_mut(|cached| *cached = Some(history));
+}
+
+pub fn record(id: &str) {
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+
+    preload();
+    let json = HISTORY.with_borrow_mut(|history| {
+        let history = history.get_or_insert_with(HashMap::new);
+        history.insert(id.to_string(), now.as_secs() as i64);
+        serde_json::to_string(history)
+    });
+
+    let Some(path) = history_path() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    match json {
+        Ok(json) => {
+            if let Err(e) = fs::write(&path, json) {
+                eprintln!("Failed to write launch history: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize launch history: {}", e),
+    }
+}
+
+pub fn last_launched(id: &str) -> Option<i64> {
+    HISTORY.with_borrow(|history| history.as_ref()?.get(id).copied())
+}
+
+fn load(path: &Path) -> HashMap<String, i64> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn history_path() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(home).join(".cache/hyprbucket/history.json"))
+}
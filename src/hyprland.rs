@@ -0,0 +1,90 @@
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+const IPC_TIMEOUT_MS: u64 = 200;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Client {
+    pub address: String,
+    #[serde(default)]
+    pub class: String,
+    #[serde(rename = "initialClass", default)]
+    pub initial_class: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Monitor {
+    pub name: String,
+    #[serde(default)]
+    pub focused: bool,
+}
+
+fn socket_path() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        let path = PathBuf::from(runtime_dir)
+            .join("hypr")
+            .join(&signature)
+            .join(".socket.sock");
+        if path.exists() {
+            return Some(path);
+        }
+    }
+
+    // Hyprland versions before 0.40 kept the socket under /tmp.
+    let legacy = PathBuf::from("/tmp/hypr")
+        .join(&signature)
+        .join(".socket.sock");
+    legacy.exists().then_some(legacy)
+}
+
+pub fn request(command: &str) -> Option<String> {
+    let path = socket_path()?;
+    let mut stream = UnixStream::connect(path).ok()?;
+
+    let timeout = Some(Duration::from_millis(IPC_TIMEOUT_MS));
+    stream.set_read_timeout(timeout).ok()?;
+    stream.set_write_timeout(timeout).ok()?;
+
+    stream.write_all(command.as_bytes()).ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    Some(response)
+}
+
+pub fn clients() -> Vec<Client> {
+    request("j/clients")
+        .and_then(|response| serde_json::from_str(&response).ok())
+        .unwrap_or_default()
+}
+
+pub fn monitors() -> Vec<Monitor> {
+    request("j/monitors")
+        .and_then(|response| serde_json::from_str(&response).ok())
+        .unwrap_or_default()
+}
+
+pub fn focused_monitor() -> Option<String> {
+    monitors()
+        .into_iter()
+        .find(|monitor| monitor.focused)
+        .map(|monitor| monitor.name)
+}
+
+pub fn dispatch(args: &str) -> bool {
+    request(&format!("dispatch {}", args))
+        .map(|response| response.trim() == "ok")
+        .unwrap_or(false)
+}
+
+pub fn focus_window(address: &str) -> bool {
+    dispatch(&format!("focuswindow address:{}", address))
+}
@@ -1,14 +1,92 @@
 use gtk4::{
     gdk::{Key, ModifierType},
     gio::prelude::ListModelExt,
-    glib::Propagation,
-    prelude::{Cast, EventControllerExt, GtkWindowExt, ObjectExt, WidgetExt},
+    glib::{Propagation, WeakRef},
+    prelude::{
+        AdjustmentExt, Cast, CastNone, EventControllerExt, GtkWindowExt, ObjectExt, ScrollableExt,
+        WidgetExt,
+    },
     ApplicationWindow, GridView,
 };
+use serde::Deserialize;
 
 use crate::launcher;
 use crate::ui::UiController;
 
+mod vim;
+use vim::Vim;
+pub use vim::VimMode;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuickLaunch {
+    #[default]
+    Alt,
+    Ctrl,
+    Super,
+    Off,
+}
+
+impl QuickLaunch {
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            QuickLaunch::Alt => Some("Alt"),
+            QuickLaunch::Ctrl => Some("Ctrl"),
+            QuickLaunch::Super => Some("Super"),
+            QuickLaunch::Off => None,
+        }
+    }
+
+    fn modifier(self) -> Option<ModifierType> {
+        match self {
+            QuickLaunch::Alt => Some(ModifierType::ALT_MASK),
+            QuickLaunch::Ctrl => Some(ModifierType::CONTROL_MASK),
+            QuickLaunch::Super => Some(ModifierType::SUPER_MASK),
+            QuickLaunch::Off => None,
+        }
+    }
+
+    fn index(self, keyval: Key, state: ModifierType) -> Option<u32> {
+        let modifier = self.modifier()?;
+        let held = state
+            & (ModifierType::ALT_MASK
+                | ModifierType::CONTROL_MASK
+                | ModifierType::SUPER_MASK
+                | ModifierType::SHIFT_MASK);
+        if held != modifier {
+            return None;
+        }
+
+        match keyval.to_unicode()?.to_digit(10)? {
+            0 => None,
+            digit => Some(digit - 1),
+        }
+    }
+}
+
+/// Modifier that flips `close_on_launch` for a single Enter. Ctrl is not
+/// offered because Ctrl+Enter already forces a new instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseToggle {
+    #[default]
+    Shift,
+    Alt,
+    Super,
+    Off,
+}
+
+impl CloseToggle {
+    fn held(self, state: ModifierType) -> bool {
+        match self {
+            CloseToggle::Shift => state.contains(ModifierType::SHIFT_MASK),
+            CloseToggle::Alt => state.contains(ModifierType::ALT_MASK),
+            CloseToggle::Super => state.contains(ModifierType::SUPER_MASK),
+            CloseToggle::Off => false,
+        }
+    }
+}
+
 pub fn setup_keybinds(
     window: &ApplicationWindow,
     grid_view: Option<&GridView>,
@@ -19,60 +97,291 @@ pub fn setup_keybinds(
 
     let window_weak = window.downgrade();
     let grid_view = grid_view.cloned();
+    let vim = ui
+        .as_ref()
+        .filter(|ui| ui.config().vim_mode)
+        .map(|_| Vim::default());
 
-    key_controller.connect_key_pressed(move |_, keyval, _, state| match keyval {
-        Key::Escape => {
-            if let Some(window) = window_weak.upgrade() {
-                window.close();
+    key_controller.connect_key_pressed(move |_, keyval, _, state| {
+        if let Some(ref vim) = vim {
+            let handled =
+                vim.key_pressed(keyval, state, &window_weak, grid_view.as_ref(), ui.as_ref());
+            if let Some(propagation) = handled {
+                return propagation;
             }
-            Propagation::Stop
         }
-        Key::Return => {
-            if let Some(ref grid_view) = grid_view {
-                launcher::launch_selected_app(grid_view);
+
+        match keyval {
+            Key::Escape if ui.as_ref().is_some_and(|ui| ui.is_multi_select()) => {
+                if let Some(ref ui) = ui {
+                    ui.toggle_multi_select();
+                }
+                Propagation::Stop
             }
-            if let Some(window) = window_weak.upgrade() {
-                window.close();
+            Key::Escape => {
+                if let Some(window) = window_weak.upgrade() {
+                    window.close();
+                }
+                Propagation::Stop
             }
-            Propagation::Stop
-        }
-        Key::Down => {
-            if let Some(ref grid_view) = grid_view {
-                move_selection(grid_view, 1);
+            Key::Return => {
+                let force_new = state.contains(ModifierType::CONTROL_MASK);
+                // The close_toggle modifier flips the configured close
+                // behaviour for this launch.
+                let close = closes_on_launch(ui.as_ref())
+                    != ui
+                        .as_ref()
+                        .map(|ui| ui.config().close_toggle)
+                        .unwrap_or_default()
+                        .held(state);
+                launch(
+                    &window_weak,
+                    grid_view.as_ref(),
+                    force_new,
+                    close,
+                    ui.as_ref(),
+                );
+                Propagation::Stop
             }
-            Propagation::Stop
-        }
-        Key::Up => {
-            if let Some(ref grid_view) = grid_view {
-                move_selection(grid_view, -1);
+            Key::Menu | Key::F10
+                if keyval == Key::Menu || state.contains(ModifierType::SHIFT_MASK) =>
+            {
+                if let Some(ref ui) = ui {
+                    ui.show_context_menu();
+                }
+                Propagation::Stop
             }
-            Propagation::Stop
-        }
-        Key::Right => {
-            if let Some(ref grid_view) = grid_view {
-                move_selection(grid_view, 1);
+            Key::space if state.contains(ModifierType::CONTROL_MASK) => {
+                if let Some(ref ui) = ui {
+                    ui.toggle_multi_select();
+                }
+                Propagation::Stop
             }
-            Propagation::Stop
-        }
-        Key::Left => {
-            if let Some(ref grid_view) = grid_view {
-                move_selection(grid_view, -1);
+            Key::space if ui.as_ref().is_some_and(|ui| ui.is_multi_select()) => {
+                if let (Some(ui), Some(grid_view)) = (ui.as_ref(), grid_view.as_ref()) {
+                    if ui.toggle_mark_selected().is_some() {
+                        move_selection(grid_view, 1, false);
+                    }
+                }
+                Propagation::Stop
             }
-            Propagation::Stop
-        }
-        Key::p | Key::P if state.contains(ModifierType::CONTROL_MASK) => {
-            if let Some(ref grid_view) = grid_view {
-                crate::launcher::toggle_pin_selected(grid_view, ui.as_ref());
+            Key::_1
+            | Key::_2
+            | Key::_3
+            | Key::_4
+            | Key::_5
+            | Key::_6
+            | Key::_7
+            | Key::_8
+            | Key::_9
+                if ui
+                    .as_ref()
+                    .is_some_and(|ui| ui.quick_launch().index(keyval, state).is_some()) =>
+            {
+                let index = ui
+                    .as_ref()
+                    .and_then(|ui| ui.quick_launch().index(keyval, state));
+                if let (Some(grid_view), Some(index)) = (grid_view.as_ref(), index) {
+                    // Launches result N even while other rows are marked.
+                    if select_index(grid_view, index)
+                        && launcher::launch_selected_app(grid_view, false, ui.as_ref())
+                        && closes_on_launch(ui.as_ref())
+                    {
+                        close_window(&window_weak);
+                    }
+                }
+                Propagation::Stop
+            }
+            Key::Up | Key::Down
+                if state.contains(ModifierType::CONTROL_MASK | ModifierType::SHIFT_MASK) =>
+            {
+                if let Some(ref ui) = ui {
+                    ui.move_selected_pin(if keyval == Key::Up { -1 } else { 1 });
+                }
+                Propagation::Stop
+            }
+            Key::Down | Key::Up => {
+                if let Some(ref grid_view) = grid_view {
+                    let step = row_step(ui.as_ref());
+                    let delta = if keyval == Key::Up { -step } else { step };
+                    move_selection(grid_view, delta, wraps(ui.as_ref()));
+                }
+                Propagation::Stop
+            }
+            Key::Right | Key::Left => {
+                if let Some(ref grid_view) = grid_view {
+                    let delta = if keyval == Key::Left { -1 } else { 1 };
+                    move_selection(grid_view, delta, wraps(ui.as_ref()));
+                }
+                Propagation::Stop
+            }
+            Key::Tab | Key::ISO_Left_Tab if state.contains(ModifierType::CONTROL_MASK) => {
+                let backwards =
+                    keyval == Key::ISO_Left_Tab || state.contains(ModifierType::SHIFT_MASK);
+                if let Some(ref ui) = ui {
+                    ui.cycle_category(if backwards { -1 } else { 1 });
+                }
+                Propagation::Stop
+            }
+            Key::Tab | Key::ISO_Left_Tab => {
+                let backwards =
+                    keyval == Key::ISO_Left_Tab || state.contains(ModifierType::SHIFT_MASK);
+                if let Some(ref grid_view) = grid_view {
+                    move_selection(grid_view, if backwards { -1 } else { 1 }, true);
+                }
+                Propagation::Stop
+            }
+            Key::n | Key::N | Key::j | Key::J if state.contains(ModifierType::CONTROL_MASK) => {
+                if let Some(ref grid_view) = grid_view {
+                    move_selection(grid_view, row_step(ui.as_ref()), wraps(ui.as_ref()));
+                }
+                Propagation::Stop
+            }
+            Key::k | Key::K if state.contains(ModifierType::CONTROL_MASK) => {
+                if let Some(ref grid_view) = grid_view {
+                    move_selection(grid_view, -row_step(ui.as_ref()), wraps(ui.as_ref()));
+                }
+                Propagation::Stop
+            }
+            Key::p | Key::P
+                if state.contains(ModifierType::CONTROL_MASK)
+                    && ui.as_ref().is_some_and(|ui| ui.config().emacs_navigation) =>
+            {
+                if let Some(ref grid_view) = grid_view {
+                    move_selection(grid_view, -row_step(ui.as_ref()), wraps(ui.as_ref()));
+                }
+                Propagation::Stop
             }
-            Propagation::Stop
+            Key::Page_Down | Key::Page_Up => {
+                if let Some(ref grid_view) = grid_view {
+                    let page = page_size(grid_view, row_step(ui.as_ref()));
+                    move_selection(
+                        grid_view,
+                        if keyval == Key::Page_Up { -page } else { page },
+                        false,
+                    );
+                }
+                Propagation::Stop
+            }
+            // Without Ctrl, Home and End keep moving the cursor in a non-empty search.
+            Key::Home | Key::End
+                if state.contains(ModifierType::CONTROL_MASK)
+                    || ui.as_ref().is_none_or(|ui| !ui.has_query()) =>
+            {
+                if let Some(ref grid_view) = grid_view {
+                    let n_items = grid_view.model().map_or(0, |model| model.n_items());
+                    if n_items > 0 {
+                        select_index(grid_view, if keyval == Key::Home { 0 } else { n_items - 1 });
+                    }
+                }
+                Propagation::Stop
+            }
+            Key::h | Key::H if state.contains(ModifierType::CONTROL_MASK) => {
+                if let Some(ref ui) = ui {
+                    if state.contains(ModifierType::SHIFT_MASK) {
+                        let showing = ui.toggle_hidden_view();
+                        println!(
+                            "{}",
+                            if showing {
+                                "Showing hidden apps"
+                            } else {
+                                "Showing apps"
+                            }
+                        );
+                    } else if let Some((name, now_hidden)) = ui.toggle_hidden_selected() {
+                        println!(
+                            "{}: {}",
+                            if now_hidden { "Hidden" } else { "Unhidden" },
+                            name
+                        );
+                    }
+                }
+                Propagation::Stop
+            }
+            Key::p | Key::P
+                if state.intersects(ModifierType::CONTROL_MASK | ModifierType::ALT_MASK) =>
+            {
+                if let Some(ref grid_view) = grid_view {
+                    crate::launcher::toggle_pin_selected(grid_view, ui.as_ref());
+                }
+                Propagation::Stop
+            }
+            _ => Propagation::Proceed,
         }
-        _ => Propagation::Proceed,
     });
 
     window.add_controller(key_controller);
 }
 
-fn move_selection(grid_view: &GridView, direction: i32) {
+fn closes_on_launch(ui: Option<&UiController>) -> bool {
+    ui.is_none_or(|ui| ui.config().close_on_launch)
+}
+
+/// Launches the marked rows, or the selected row when nothing is marked, and
+/// closes the window if `close` is set and every launch succeeded.
+fn launch(
+    window: &WeakRef<ApplicationWindow>,
+    grid_view: Option<&GridView>,
+    force_new: bool,
+    close: bool,
+    ui: Option<&UiController>,
+) {
+    let marked = ui.map(UiController::take_marked).unwrap_or_default();
+    let launched = if marked.is_empty() {
+        grid_view.is_none_or(|grid_view| launcher::launch_selected_app(grid_view, force_new, ui))
+    } else {
+        launcher::launch_all(&marked, force_new, ui)
+    };
+
+    if launched && close {
+        close_window(window);
+    }
+}
+
+fn close_window(window: &WeakRef<ApplicationWindow>) {
+    if let Some(window) = window.upgrade() {
+        window.close();
+    }
+}
+
+fn select_index(grid_view: &GridView, index: u32) -> bool {
+    let Some(selection) = grid_view.model().and_downcast::<gtk4::SingleSelection>() else {
+        return false;
+    };
+    if index >= selection.n_items() {
+        return false;
+    }
+
+    selection.set_selected(index);
+    true
+}
+
+fn wraps(ui: Option<&UiController>) -> bool {
+    ui.is_some_and(|ui| ui.config().wrap_navigation)
+}
+
+fn row_step(ui: Option<&UiController>) -> i32 {
+    ui.map_or(1, |ui| ui.columns() as i32)
+}
+
+/// Number of results in the lines that fit in the visible part of the list,
+/// estimated from the scrollable height so it follows the window size and
+/// row styling.
+fn page_size(grid_view: &GridView, columns: i32) -> i32 {
+    let n_items = grid_view.model().map_or(0, |model| model.n_items());
+    let Some(adjustment) = grid_view.vadjustment() else {
+        return columns;
+    };
+    if n_items == 0 || adjustment.upper() <= 0.0 {
+        return columns;
+    }
+
+    let lines = n_items.div_ceil(columns as u32);
+    let line_height = adjustment.upper() / f64::from(lines);
+    ((adjustment.page_size() / line_height).floor() as i32).max(1) * columns
+}
+
+fn move_selection(grid_view: &GridView, delta: i32, wrap: bool) {
     grid_view.set_can_target(false);
 
     let Some(model) = grid_view.model() else {
@@ -89,20 +398,17 @@ fn move_selection(grid_view: &GridView, direction: i32) {
         return;
     }
 
-    let new_pos = if direction > 0 {
-        if current == u32::MAX {
-            0
-        } else {
-            (current + 1).min(n_items - 1)
-        }
+    let new_pos = if current == u32::MAX {
+        0
     } else {
-        if current == u32::MAX || current == 0 {
-            0
+        let target = i64::from(current) + i64::from(delta);
+        let last = i64::from(n_items) - 1;
+        if wrap && !(0..=last).contains(&target) {
+            target.rem_euclid(last + 1)
         } else {
-            current - 1
+            target.clamp(0, last)
         }
     };
 
-    selection.set_selected(new_pos);
+    selection.set_selected(new_pos as u32);
 }
-
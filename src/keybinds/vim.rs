@@ -0,0 +1,143 @@
+use std::cell::Cell;
+
+use gtk4::{
+    gdk::{Key, ModifierType},
+    gio::prelude::ListModelExt,
+    glib::{Propagation, WeakRef},
+    prelude::GtkWindowExt,
+    ApplicationWindow, GridView,
+};
+
+use super::{move_selection, row_step, select_index, wraps};
+use crate::ui::UiController;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VimMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+impl VimMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Insert => "INSERT",
+            VimMode::Normal => "NORMAL",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Vim {
+    mode: Cell<VimMode>,
+    pending: Cell<Option<char>>,
+}
+
+impl Vim {
+    /// Handles a key press for the current mode, or returns `None` to let the
+    /// regular keybinds see it.
+    pub fn key_pressed(
+        &self,
+        keyval: Key,
+        state: ModifierType,
+        window: &WeakRef<ApplicationWindow>,
+        grid_view: Option<&GridView>,
+        ui: Option<&UiController>,
+    ) -> Option<Propagation> {
+        if self.mode.get() == VimMode::Insert {
+            if keyval != Key::Escape {
+                return None;
+            }
+            self.set_mode(VimMode::Normal, ui);
+            return Some(Propagation::Stop);
+        }
+
+        // Ctrl and Alt shortcuts, arrows, Return and the like keep working as usual.
+        if state.intersects(ModifierType::CONTROL_MASK | ModifierType::ALT_MASK) {
+            self.pending.set(None);
+            return None;
+        }
+
+        match keyval {
+            Key::Escape => {
+                if let Some(window) = window.upgrade() {
+                    window.close();
+                }
+                return Some(Propagation::Stop);
+            }
+            // Editing keys would still change the query through the focused entry.
+            Key::BackSpace | Key::Delete => return Some(Propagation::Stop),
+            // Marking rows in multi-select mode.
+            Key::space if ui.is_some_and(UiController::is_multi_select) => return None,
+            _ => {}
+        }
+
+        let Some(c) = keyval.to_unicode().filter(|c| !c.is_control()) else {
+            self.pending.set(None);
+            return None;
+        };
+
+        let pending = self.pending.take();
+        match (pending, c) {
+            (_, 'j' | 'k') => {
+                if let Some(grid_view) = grid_view {
+                    let step = row_step(ui);
+                    let delta = if c == 'k' { -step } else { step };
+                    move_selection(grid_view, delta, wraps(ui));
+                }
+            }
+            (_, 'h' | 'l') => {
+                if let Some(grid_view) = grid_view {
+                    move_selection(grid_view, if c == 'h' { -1 } else { 1 }, wraps(ui));
+                }
+            }
+            (Some('g'), 'g') => {
+                if let Some(grid_view) = grid_view {
+                    select_index(grid_view, 0);
+                }
+            }
+            (_, 'G') => {
+                if let Some(grid_view) = grid_view {
+                    let n_items = grid_view.model().map_or(0, |model| model.n_items());
+                    if n_items > 0 {
+                        select_index(grid_view, n_items - 1);
+                    }
+                }
+            }
+            (Some('d'), 'd') => {
+                if let Some(ui) = ui {
+                    unpin_selected(ui);
+                }
+            }
+            (None, 'g' | 'd') => self.pending.set(Some(c)),
+            (_, '/' | 'i' | 'a') => {
+                self.set_mode(VimMode::Insert, ui);
+                if let Some(ui) = ui {
+                    ui.focus_search(c == '/');
+                }
+            }
+            _ => {}
+        }
+
+        // Unmapped printable keys must not reach the search entry.
+        Some(Propagation::Stop)
+    }
+
+    fn set_mode(&self, mode: VimMode, ui: Option<&UiController>) {
+        self.mode.set(mode);
+        self.pending.set(None);
+        if let Some(ui) = ui {
+            ui.set_vim_mode(mode);
+        }
+    }
+}
+
+fn unpin_selected(ui: &UiController) {
+    let Some(id) = ui.selected_app_id() else {
+        return;
+    };
+    if ui.is_pinned(&id) {
+        ui.toggle_pin(&id);
+        println!("Unpinned: {}", id);
+    }
+}
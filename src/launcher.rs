@@ -1,12 +1,46 @@
-use gtk4::{prelude::Cast, GridView};
-use std::process::Command;
+use gtk4::{gio::prelude::ApplicationExtManual, glib, prelude::Cast, GridView};
+use std::{
+    collections::HashMap,
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
+
+pub mod activation;
+pub mod backend;
+pub mod dbus;
+pub mod report;
+pub mod terminal;
 
 use crate::{
     config::Config,
     desktop::DesktopEntry,
+    history, hyprland, single_instance,
     ui::{AppEntryObject, UiController},
 };
 
+use activation::ActivationToken;
+use backend::{Capture, LaunchRequest};
+use report::LaunchFailure;
+use terminal::Terminal;
+
+type ExecutableCache = Mutex<HashMap<String, Option<PathBuf>>>;
+
+static EXECUTABLES: OnceLock<ExecutableCache> = OnceLock::new();
+static LAUNCH_COUNT: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub force_new: bool,
+    pub in_terminal: bool,
+    pub action: Option<String>,
+}
+
 fn get_selected_entry(grid_view: &GridView) -> Option<DesktopEntry> {
     let model = grid_view.model()?;
     let selection = model.downcast_ref::<gtk4::SingleSelection>()?;
@@ -21,72 +55,349 @@ fn get_selected_entry(grid_view: &GridView) -> Option<DesktopEntry> {
         .map(|obj| obj.entry())
 }
 
-pub fn launch_selected_app(grid_view: &GridView) {
-    if let Some(entry) = get_selected_entry(grid_view) {
-        launch_app(&entry);
+/// Launches the selected app and returns whether the launcher may close,
+/// which is not the case when the failure is being shown in the window.
+pub fn launch_selected_app(
+    grid_view: &GridView,
+    force_new: bool,
+    ui: Option<&UiController>,
+) -> bool {
+    match get_selected_entry(grid_view) {
+        Some(entry) => launch_app(&entry, force_new, ui),
+        None => true,
+    }
+}
+
+pub fn launch_app(entry: &DesktopEntry, force_new: bool, ui: Option<&UiController>) -> bool {
+    let options = LaunchOptions {
+        force_new,
+        ..LaunchOptions::default()
+    };
+    launch_app_with(entry, &options, ui)
+}
+
+pub fn launch_app_with(
+    entry: &DesktopEntry,
+    options: &LaunchOptions,
+    ui: Option<&UiController>,
+) -> bool {
+    match try_launch(entry, options, ui) {
+        Ok(()) => {
+            history::record(&entry.id);
+            true
+        }
+        Err(failure) => {
+            report::report(&failure, ui);
+            false
+        }
     }
 }
 
-pub fn launch_app(entry: &DesktopEntry) {
-    let exec = entry
-        .exec
+pub fn launch_all(entries: &[DesktopEntry], force_new: bool, ui: Option<&UiController>) -> bool {
+    let mut all_launched = true;
+    for entry in entries {
+        all_launched &= launch_app(entry, force_new, ui);
+    }
+    all_launched
+}
+
+fn try_launch(
+    entry: &DesktopEntry,
+    options: &LaunchOptions,
+    ui: Option<&UiController>,
+) -> Result<(), LaunchFailure> {
+    let config = Config::load();
+
+    let plain = options.action.is_none() && !options.in_terminal;
+    if plain && !options.force_new && config.focus_running && focus_running_instance(entry) {
+        return Ok(());
+    }
+
+    let token = if entry.startup_notify != Some(false) {
+        ActivationToken::request(entry)
+    } else {
+        None
+    };
+
+    // Overrides from `[apps]` only apply to the Exec line, so they win over D-Bus.
+    let overridden = config.app(&entry.id).is_some_and(|app| {
+        !app.env.is_empty() || !app.args.is_empty() || app.launch_backend.is_some()
+    });
+
+    if entry.dbus_activatable && !options.in_terminal && !overridden {
+        if let Some(connection) = dbus::session_bus() {
+            println!("Activating over D-Bus: {}", entry.name);
+
+            let fallback_entry = entry.clone();
+            let fallback_options = options.clone();
+            let fallback_ui = ui.cloned();
+            let action = options.action.as_deref();
+            dbus::activate(&connection, entry, action, token, move |token| {
+                let config = Config::load();
+                let ui = fallback_ui.as_ref();
+                if let Err(failure) =
+                    spawn_exec(&fallback_entry, &fallback_options, &config, token, ui)
+                {
+                    report::report(&failure, ui);
+                }
+            });
+            return Ok(());
+        }
+    }
+
+    spawn_exec(entry, options, &config, token, ui)
+}
+
+/// Runs the Exec line, letting GDK drop the startup sequence of `token` on
+/// every path that ends without a spawned process.
+fn spawn_exec(
+    entry: &DesktopEntry,
+    options: &LaunchOptions,
+    config: &Config,
+    token: Option<ActivationToken>,
+    ui: Option<&UiController>,
+) -> Result<(), LaunchFailure> {
+    let result = spawn_exec_line(entry, options, config, token.as_ref(), ui);
+
+    if let (Err(_), Some(token)) = (&result, &token) {
+        token.launch_failed();
+    }
+
+    result
+}
+
+fn spawn_exec_line(
+    entry: &DesktopEntry,
+    options: &LaunchOptions,
+    config: &Config,
+    token: Option<&ActivationToken>,
+    ui: Option<&UiController>,
+) -> Result<(), LaunchFailure> {
+    let fail = |reason: String| LaunchFailure::new(&entry.name, entry.icon.as_deref(), reason);
+
+    let app = config.app(&entry.id).cloned().unwrap_or_default();
+
+    let exec_line = match &options.action {
+        Some(id) => {
+            let action = entry
+                .actions
+                .iter()
+                .find(|action| &action.id == id)
+                .ok_or_else(|| fail(format!("Unknown desktop action `{}`", id)))?;
+            &action.exec
+        }
+        None => &entry.exec,
+    };
+
+    let mut exec = exec_line
         .split_whitespace()
         .filter(|arg| !arg.starts_with('%'))
         .collect::<Vec<_>>()
         .join(" ");
 
+    // Extra arguments belong to the main command, not to desktop actions.
+    for arg in app.args.iter().filter(|_| options.action.is_none()) {
+        exec.push(' ');
+        exec.push_str(&shell_quote(arg));
+    }
+
     println!("Launching: {} ({})", entry.name, exec);
 
-    let result = if entry.terminal {
-        let terminal = find_terminal();
-        Command::new("sh")
-            .arg("-c")
-            .arg(format!(
-                "{} -e sh -c '{}'",
-                terminal,
-                exec.replace("'", "\\'")
-            ))
-            .spawn()
+    match exec_program(&exec) {
+        Some(program) if is_plain_word(&program) && find_executable(&program).is_none() => {
+            return Err(fail(format!("`{}` was not found in PATH", program)));
+        }
+        None => return Err(fail("Exec line is empty".to_string())),
+        _ => {}
+    }
+
+    let command_line = if options.in_terminal || app.terminal.unwrap_or(entry.terminal) {
+        let terminal =
+            Terminal::resolve(config.terminal.as_deref(), config.terminal_args.as_deref())
+                .ok_or_else(|| {
+                    fail("No terminal emulator found, set `terminal` in config.toml".to_string())
+                })?;
+        terminal.wrap(&exec)
     } else {
-        Command::new("sh").arg("-c").arg(&exec).spawn()
+        exec
     };
 
-    match result {
-        Ok(_) => println!("Launched: {}", entry.name),
-        Err(e) => eprintln!("Failed to launch {}: {}", entry.name, e),
+    let launch_backend = app.launch_backend.unwrap_or(config.launch_backend);
+
+    let capture = launch_backend
+        .supports_capture()
+        .then(|| capture_paths(&entry.id))
+        .flatten();
+
+    let mut env = token.map(ActivationToken::env).unwrap_or_default();
+    env.extend(app.env);
+
+    let request = LaunchRequest {
+        entry,
+        command_line,
+        env,
+        working_dir: entry.working_dir.as_deref(),
+        exec_rules: config.hyprland_exec_rules.as_deref(),
+        capture,
+    };
+
+    backend::spawn(launch_backend, &request).map_err(|e| fail(e.to_string()))?;
+
+    println!("Launched: {}", entry.name);
+
+    if let Some(capture) = request.capture {
+        watch_launch(
+            fail(String::new()),
+            capture,
+            Duration::from_millis(config.launch_grace_ms),
+            ui.cloned(),
+        );
     }
+
+    Ok(())
 }
 
-fn find_terminal() -> &'static str {
-    let terminals = [
-        "kitty",
-        "alacritty",
-        "wezterm",
-        "foot",
-        "gnome-terminal",
-        "konsole",
-        "xterm",
-    ];
+/// Checks once the grace period is over whether the app already exited with
+/// an error, keeping the process alive until then.
+fn watch_launch(
+    failure: LaunchFailure,
+    capture: Capture,
+    grace: Duration,
+    ui: Option<UiController>,
+) {
+    let hold = gtk4::gio::Application::default().map(|app| app.hold());
 
-    for terminal in &terminals {
-        if Command::new("which")
-            .arg(terminal)
-            .output()
+    glib::timeout_add_local_once(grace, move || {
+        let _hold = hold;
+
+        let code = fs::read_to_string(&capture.status)
             .ok()
-            .and_then(|output| {
-                if output.status.success() {
-                    Some(())
-                } else {
-                    None
-                }
-            })
-            .is_some()
-        {
-            return terminal;
+            .and_then(|status| status.trim().parse::<i32>().ok());
+
+        if let Some(code) = code.filter(|code| *code != 0) {
+            let mut failure = failure;
+            failure.reason = format!("Exited with status {}", code);
+            failure.stderr_tail = report::stderr_tail(&capture.log);
+            report::report(&failure, ui.as_ref());
         }
+
+        let _ = fs::remove_file(&capture.log);
+        let _ = fs::remove_file(&capture.status);
+    });
+}
+
+fn capture_paths(desktop_id: &str) -> Option<Capture> {
+    let dir = single_instance::runtime_dir().join("launches");
+    fs::create_dir_all(&dir).ok()?;
+
+    // Launches of the same app within the grace period each get their own files.
+    let stem = format!(
+        "{}-{}-{}",
+        desktop_id.strip_suffix(".desktop").unwrap_or(desktop_id),
+        std::process::id(),
+        LAUNCH_COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+
+    Some(Capture {
+        log: dir.join(format!("{}.log", stem)),
+        status: dir.join(format!("{}.status", stem)),
+    })
+}
+
+/// The program an Exec line runs, skipping a leading `env VAR=value ...`.
+/// Quotes are removed and a leading `~/` is expanded the way `sh` would.
+fn exec_program(exec: &str) -> Option<String> {
+    let mut rest = exec.trim_start();
+
+    loop {
+        let (token, remainder) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let quoted = &rest[1..];
+                let end = quoted.find(quote)?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            }
+        };
+
+        if token.is_empty() {
+            return None;
+        }
+
+        if token == "env" || (token.contains('=') && !token.starts_with('/')) {
+            rest = remainder.trim_start();
+            continue;
+        }
+
+        if let (Some(path), Some(home)) = (token.strip_prefix("~/"), std::env::var_os("HOME")) {
+            return Some(Path::new(&home).join(path).to_string_lossy().into_owned());
+        }
+
+        return Some(token.to_string());
+    }
+}
+
+/// Whether `program` is a literal name or path rather than something `sh`
+/// still expands, which cannot be looked up before running it.
+fn is_plain_word(program: &str) -> bool {
+    !program.starts_with('~') && !program.contains(|c| "$`\\*?[]{}()<>|&;".contains(c))
+}
+
+fn focus_running_instance(entry: &DesktopEntry) -> bool {
+    let Some(client) = hyprland::clients().into_iter().find(|client| {
+        entry.matches_window_class(&client.class)
+            || entry.matches_window_class(&client.initial_class)
+    }) else {
+        return false;
+    };
+
+    if hyprland::focus_window(&client.address) {
+        println!("Focused running instance: {}", entry.name);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        let path = Path::new(name);
+        return is_executable(path).then(|| path.to_path_buf());
+    }
+
+    let cache = EXECUTABLES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(found) = cache.lock().ok()?.get(name) {
+        return found.clone();
+    }
+
+    let found = std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|candidate| is_executable(candidate))
+    });
+
+    cache.lock().ok()?.insert(name.to_string(), found.clone());
+    found
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+pub fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c))
+    {
+        return value.to_string();
     }
 
-    "sh"
+    format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 pub fn toggle_pin_selected(grid_view: &GridView, ui: Option<&UiController>) {
@@ -112,3 +423,48 @@ pub fn toggle_pin_selected(grid_view: &GridView, ui: Option<&UiController>) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exec_program_skips_env_assignments() {
+        assert_eq!(
+            exec_program("env GDK_BACKEND=wayland firefox --new-window").as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(exec_program("FOO=1 bar").as_deref(), Some("bar"));
+        assert_eq!(exec_program("   "), None);
+    }
+
+    #[test]
+    fn exec_program_unquotes_the_program() {
+        assert_eq!(
+            exec_program("'/opt/My App/app' --flag").as_deref(),
+            Some("/opt/My App/app")
+        );
+        assert_eq!(
+            exec_program("\"/opt/My App/app\"").as_deref(),
+            Some("/opt/My App/app")
+        );
+    }
+
+    #[test]
+    fn exec_program_expands_home() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            exec_program("~/bin/foo --bar"),
+            Some(format!("{}/bin/foo", home))
+        );
+    }
+
+    #[test]
+    fn only_plain_words_are_looked_up() {
+        assert!(is_plain_word("firefox"));
+        assert!(is_plain_word("/opt/My App/app"));
+        assert!(!is_plain_word("$HOME/bin/foo"));
+        assert!(!is_plain_word("~user/bin/foo"));
+        assert!(!is_plain_word("foo;bar"));
+    }
+}
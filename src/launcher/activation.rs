@@ -0,0 +1,45 @@
+use gtk4::{
+    gdk::{self, prelude::DisplayExt},
+    gio::{self, prelude::AppLaunchContextExt},
+};
+
+use crate::desktop::DesktopEntry;
+
+/// A startup notification id obtained from GDK, which on Wayland is an
+/// `xdg_activation_v1` token tied to the launcher's focused surface.
+pub struct ActivationToken {
+    context: gdk::AppLaunchContext,
+    token: String,
+}
+
+impl ActivationToken {
+    /// Must be called while the launcher window still has focus, otherwise
+    /// the compositor is free to hand out a token that does not grant focus.
+    pub fn request(entry: &DesktopEntry) -> Option<Self> {
+        let display = gdk::Display::default()?;
+        let context = display.app_launch_context();
+        let info = gio::DesktopAppInfo::new(&entry.id);
+
+        let token = context.startup_notify_id(info.as_ref(), &[])?;
+
+        Some(Self {
+            context,
+            token: token.to_string(),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    pub fn env(&self) -> Vec<(String, String)> {
+        vec![
+            ("XDG_ACTIVATION_TOKEN".to_string(), self.token.clone()),
+            ("DESKTOP_STARTUP_ID".to_string(), self.token.clone()),
+        ]
+    }
+
+    pub fn launch_failed(&self) {
+        self.context.launch_failed(&self.token);
+    }
+}
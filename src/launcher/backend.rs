@@ -0,0 +1,538 @@
+use std::{
+    io,
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+
+use super::shell_quote;
+use crate::{desktop::DesktopEntry, hyprland};
+
+const UNIT_PREFIX: &str = "app-hyprbucket";
+const LOG_LIMIT_BYTES: u32 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchBackend {
+    #[default]
+    Direct,
+    Hyprland,
+    Systemd,
+    Uwsm,
+}
+
+pub struct Capture {
+    pub log: PathBuf,
+    pub status: PathBuf,
+}
+
+impl LaunchBackend {
+    pub fn supports_capture(self) -> bool {
+        self != LaunchBackend::Hyprland
+    }
+}
+
+pub struct LaunchRequest<'a> {
+    pub entry: &'a DesktopEntry,
+    pub command_line: String,
+    pub env: Vec<(String, String)>,
+    pub working_dir: Option<&'a str>,
+    pub exec_rules: Option<&'a str>,
+    pub capture: Option<Capture>,
+}
+
+pub fn spawn(backend: LaunchBackend, request: &LaunchRequest) -> io::Result<()> {
+    match backend {
+        LaunchBackend::Hyprland => dispatch_exec(request, hyprland::dispatch),
+        _ => spawn_detached(
+            &command_argv(backend, request.entry, &request.command_line),
+            request,
+        ),
+    }
+}
+
+fn command_argv(backend: LaunchBackend, entry: &DesktopEntry, command_line: &str) -> Vec<String> {
+    let shell = ["sh".to_string(), "-c".to_string(), command_line.to_string()];
+
+    let mut argv: Vec<String> = match backend {
+        LaunchBackend::Direct | LaunchBackend::Hyprland => Vec::new(),
+        LaunchBackend::Systemd => vec![
+            "systemd-run".to_string(),
+            "--user".to_string(),
+            "--scope".to_string(),
+            "--quiet".to_string(),
+            format!("--unit={}", unit_name(&entry.id)),
+            format!("--description={}", entry.name),
+            "--".to_string(),
+        ],
+        LaunchBackend::Uwsm => vec![
+            "uwsm".to_string(),
+            "app".to_string(),
+            "-a".to_string(),
+            app_name(&entry.id).to_string(),
+            "--".to_string(),
+        ],
+    };
+
+    argv.extend(shell);
+    argv
+}
+
+fn dispatch_exec(request: &LaunchRequest, dispatch: impl FnOnce(&str) -> bool) -> io::Result<()> {
+    if dispatch(&dispatch_args(request)) {
+        Ok(())
+    } else {
+        Err(io::Error::other("Hyprland did not accept dispatch exec"))
+    }
+}
+
+fn dispatch_args(request: &LaunchRequest) -> String {
+    // Hyprland starts the command itself, so the environment and working
+    // directory have to travel with it.
+    let mut command_line = request.command_line.clone();
+
+    if let Some(dir) = request.working_dir {
+        let script = format!("cd {} && exec {}", shell_quote(dir), command_line);
+        command_line = format!("sh -c {}", shell_quote(&script));
+    }
+
+    if !request.env.is_empty() {
+        let assignments: Vec<String> = request
+            .env
+            .iter()
+            .map(|(key, value)| shell_quote(&format!("{}={}", key, value)))
+            .collect();
+        command_line = format!("env {} {}", assignments.join(" "), command_line);
+    }
+
+    match request
+        .exec_rules
+        .map(str::trim)
+        .filter(|rules| !rules.is_empty())
+    {
+        Some(rules) if rules.starts_with('[') => format!("exec {} {}", rules, command_line),
+        Some(rules) => format!("exec [{}] {}", rules, command_line),
+        None => format!("exec {}", command_line),
+    }
+}
+
+/// Runs `argv` in the background of a short-lived `sh` started in its own
+/// session, so the app ends up reparented to init instead of the launcher.
+///
+/// With a capture, the backgrounded subshell stays around as the app's parent
+/// for its whole lifetime so it can record the exit status. Only the first
+/// [`LOG_LIMIT_BYTES`] of stderr are written to the log, the rest is drained by
+/// a `cat` so chatty apps neither fill the tmpfs nor get `SIGPIPE`. The log is
+/// opened before the wrapper returns and the status is only written while the
+/// log exists, so an app outliving the watcher that removed both files leaves
+/// neither behind.
+fn spawn_detached(argv: &[String], request: &LaunchRequest) -> io::Result<()> {
+    let mut command = Command::new("sh");
+    command.arg("-c");
+
+    match &request.capture {
+        Some(capture) => {
+            command
+                .arg(
+                    "log=$1 status=$2 limit=$3; shift 3; exec 4>\"$log\"; \
+                     { { \"$@\" 2>&1 >&3 3>&- 4>&-; code=$?; \
+                     [ -e \"$log\" ] && echo $code >\"$status\"; } \
+                     | { head -c \"$limit\" >&4 4>&-; cat >/dev/null 4>&-; }; } 3>&1 &",
+                )
+                .arg("sh")
+                .arg(&capture.log)
+                .arg(&capture.status)
+                .arg(LOG_LIMIT_BYTES.to_string());
+        }
+        None => {
+            command.arg("\"$@\" &").arg("sh");
+        }
+    }
+
+    command
+        .args(argv)
+        .envs(request.env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null());
+
+    if let Some(dir) = request.working_dir {
+        command.current_dir(dir);
+    }
+
+    unsafe {
+        command.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "launch wrapper exited with {}",
+            status
+        )))
+    }
+}
+
+fn app_name(desktop_id: &str) -> &str {
+    desktop_id.strip_suffix(".desktop").unwrap_or(desktop_id)
+}
+
+/// Follows the `app-<launcher>-<desktop id>-<random>.scope` naming from the
+/// systemd desktop environment guidelines.
+fn unit_name(desktop_id: &str) -> String {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default()
+        ^ std::process::id();
+
+    format!(
+        "{}-{}-{:08x}.scope",
+        UNIT_PREFIX,
+        systemd_escape(app_name(desktop_id)),
+        nonce
+    )
+}
+
+fn systemd_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for (i, byte) in value.bytes().enumerate() {
+        let keep =
+            byte.is_ascii_alphanumeric() || byte == b'_' || byte == b':' || (byte == b'.' && i > 0);
+
+        if keep {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, os::unix::fs::PermissionsExt, path::Path, thread, time::Duration};
+
+    fn entry(id: &str) -> DesktopEntry {
+        DesktopEntry {
+            id: id.to_string(),
+            name: "Test App".to_string(),
+            ..DesktopEntry::default()
+        }
+    }
+
+    fn request<'a>(entry: &'a DesktopEntry, command_line: &str) -> LaunchRequest<'a> {
+        LaunchRequest {
+            entry,
+            command_line: command_line.to_string(),
+            env: Vec::new(),
+            working_dir: None,
+            exec_rules: None,
+            capture: None,
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("hbucket-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Puts an executable into `dir` that records its name, arguments and
+    /// working directory in `$STUB_ARGS`.
+    fn write_stub(dir: &Path, program: &str) {
+        let path = dir.join(program);
+        fs::write(
+            &path,
+            "#!/bin/sh\n{ printf '%s\\n' \"${0##*/}\" \"$@\"; pwd; } >\"$STUB_ARGS\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn run_stubbed(
+        backend: LaunchBackend,
+        dir: &Path,
+        command_line: &str,
+        working_dir: Option<&str>,
+    ) -> Vec<String> {
+        let entry = entry("org.example.App.desktop");
+        let args_file = dir.join("args");
+        let capture = Capture {
+            log: dir.join("log"),
+            status: dir.join("status"),
+        };
+        let status_file = capture.status.clone();
+
+        let path = format!(
+            "{}:{}",
+            dir.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let request = LaunchRequest {
+            env: vec![
+                ("PATH".to_string(), path),
+                ("STUB_ARGS".to_string(), args_file.display().to_string()),
+            ],
+            working_dir,
+            capture: Some(capture),
+            ..request(&entry, command_line)
+        };
+
+        spawn(backend, &request).unwrap();
+
+        assert_eq!(wait_for_status(&status_file), "0");
+        fs::read_to_string(&args_file)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn wait_for_status(path: &Path) -> String {
+        for _ in 0..100 {
+            if let Ok(status) = fs::read_to_string(path) {
+                if !status.trim().is_empty() {
+                    return status.trim().to_string();
+                }
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        panic!("launch wrapper did not record an exit status");
+    }
+
+    #[test]
+    fn direct_argv_runs_the_command_line_through_sh() {
+        let entry = entry("firefox.desktop");
+        assert_eq!(
+            command_argv(LaunchBackend::Direct, &entry, "firefox --new-window"),
+            strings(&["sh", "-c", "firefox --new-window"])
+        );
+    }
+
+    #[test]
+    fn systemd_argv_starts_a_named_user_scope() {
+        let entry = entry("org.gnome.Nautilus.desktop");
+        let argv = command_argv(LaunchBackend::Systemd, &entry, "nautilus");
+
+        assert_eq!(
+            argv[..4],
+            strings(&["systemd-run", "--user", "--scope", "--quiet"])
+        );
+        assert!(argv[4].starts_with("--unit=app-hyprbucket-org.gnome.Nautilus-"));
+        assert!(argv[4].ends_with(".scope"));
+        assert_eq!(argv[5], "--description=Test App");
+        assert_eq!(argv[6..], strings(&["--", "sh", "-c", "nautilus"]));
+    }
+
+    #[test]
+    fn uwsm_argv_passes_the_app_name() {
+        let entry = entry("kitty.desktop");
+        assert_eq!(
+            command_argv(LaunchBackend::Uwsm, &entry, "kitty"),
+            strings(&["uwsm", "app", "-a", "kitty", "--", "sh", "-c", "kitty"])
+        );
+    }
+
+    #[test]
+    fn systemd_escape_escapes_dashes_spaces_and_a_leading_dot() {
+        assert_eq!(systemd_escape("org.gnome.Nautilus"), "org.gnome.Nautilus");
+        assert_eq!(systemd_escape("my-app_1:2"), "my\\x2dapp_1:2");
+        assert_eq!(systemd_escape("a b"), "a\\x20b");
+        assert_eq!(systemd_escape(".hidden.app"), "\\x2ehidden.app");
+    }
+
+    #[test]
+    fn unit_name_strips_desktop_and_adds_scope_suffix() {
+        let unit = unit_name("my-app.desktop");
+        let nonce = unit
+            .strip_prefix("app-hyprbucket-my\\x2dapp-")
+            .and_then(|rest| rest.strip_suffix(".scope"))
+            .unwrap();
+
+        assert_eq!(nonce.len(), 8);
+        assert!(nonce.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn dispatch_args_without_rules() {
+        let entry = entry("firefox.desktop");
+        assert_eq!(dispatch_args(&request(&entry, "firefox")), "exec firefox");
+
+        let blank = LaunchRequest {
+            exec_rules: Some("  "),
+            ..request(&entry, "firefox")
+        };
+        assert_eq!(dispatch_args(&blank), "exec firefox");
+    }
+
+    #[test]
+    fn dispatch_args_brackets_rules_when_needed() {
+        let entry = entry("firefox.desktop");
+
+        let bare = LaunchRequest {
+            exec_rules: Some("workspace 3 silent"),
+            ..request(&entry, "firefox")
+        };
+        assert_eq!(dispatch_args(&bare), "exec [workspace 3 silent] firefox");
+
+        let bracketed = LaunchRequest {
+            exec_rules: Some(" [workspace 3; float] "),
+            ..request(&entry, "firefox")
+        };
+        assert_eq!(
+            dispatch_args(&bracketed),
+            "exec [workspace 3; float] firefox"
+        );
+    }
+
+    #[test]
+    fn dispatch_args_wraps_env_and_working_dir() {
+        let entry = entry("firefox.desktop");
+        let request = LaunchRequest {
+            env: vec![
+                ("MOZ_ENABLE_WAYLAND".to_string(), "1".to_string()),
+                ("GREETING".to_string(), "hello world".to_string()),
+            ],
+            working_dir: Some("/tmp/my dir"),
+            ..request(&entry, "firefox")
+        };
+
+        assert_eq!(
+            dispatch_args(&request),
+            "exec env MOZ_ENABLE_WAYLAND=1 'GREETING=hello world' \
+             sh -c 'cd '\\''/tmp/my dir'\\'' && exec firefox'"
+        );
+    }
+
+    #[test]
+    fn dispatch_exec_reports_a_rejected_dispatch() {
+        let entry = entry("firefox.desktop");
+        let request = request(&entry, "firefox");
+
+        let mut sent = None;
+        dispatch_exec(&request, |args| {
+            sent = Some(args.to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(sent.as_deref(), Some("exec firefox"));
+
+        assert!(dispatch_exec(&request, |_| false).is_err());
+    }
+
+    #[test]
+    fn direct_spawn_runs_in_the_working_dir() {
+        let dir = temp_dir("direct");
+        write_stub(&dir, "fake-app");
+        let working_dir = dir.display().to_string();
+
+        let recorded = run_stubbed(
+            LaunchBackend::Direct,
+            &dir,
+            "fake-app --flag",
+            Some(&working_dir),
+        );
+
+        assert_eq!(recorded, strings(&["fake-app", "--flag", &working_dir]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn systemd_spawn_goes_through_systemd_run() {
+        let dir = temp_dir("systemd");
+        write_stub(&dir, "systemd-run");
+
+        let recorded = run_stubbed(LaunchBackend::Systemd, &dir, "fake-app", None);
+
+        assert_eq!(recorded[0], "systemd-run");
+        assert!(recorded[4].starts_with("--unit=app-hyprbucket-org.example.App-"));
+        assert_eq!(recorded[6..10], strings(&["--", "sh", "-c", "fake-app"]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn uwsm_spawn_goes_through_uwsm_app() {
+        let dir = temp_dir("uwsm");
+        write_stub(&dir, "uwsm");
+
+        let recorded = run_stubbed(LaunchBackend::Uwsm, &dir, "fake-app", None);
+
+        assert_eq!(
+            recorded[..8],
+            strings(&[
+                "uwsm",
+                "app",
+                "-a",
+                "org.example.App",
+                "--",
+                "sh",
+                "-c",
+                "fake-app"
+            ])
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn capture_caps_the_stderr_log_and_keeps_the_exit_status() {
+        let dir = temp_dir("capture");
+        let entry = entry("chatty.desktop");
+        let capture = Capture {
+            log: dir.join("log"),
+            status: dir.join("status"),
+        };
+        let (log, status) = (capture.log.clone(), capture.status.clone());
+        let request = LaunchRequest {
+            capture: Some(capture),
+            ..request(&entry, "head -c 200000 /dev/zero >&2; exit 3")
+        };
+
+        spawn(LaunchBackend::Direct, &request).unwrap();
+
+        assert_eq!(wait_for_status(&status), "3");
+        assert_eq!(
+            fs::metadata(&log).unwrap().len(),
+            u64::from(LOG_LIMIT_BYTES)
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn capture_skips_the_status_once_the_log_is_removed() {
+        let dir = temp_dir("cleanup");
+        let entry = entry("slow.desktop");
+        let capture = Capture {
+            log: dir.join("log"),
+            status: dir.join("status"),
+        };
+        let (log, status) = (capture.log.clone(), capture.status.clone());
+        let request = LaunchRequest {
+            capture: Some(capture),
+            ..request(&entry, "sleep 0.2; exit 2")
+        };
+
+        spawn(LaunchBackend::Direct, &request).unwrap();
+        fs::remove_file(&log).unwrap();
+        thread::sleep(Duration::from_millis(600));
+
+        assert!(!status.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
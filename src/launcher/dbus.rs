@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use gtk4::{
+    gio::{self, prelude::*, BusType, DBusCallFlags, DBusConnection},
+    glib::{self, prelude::ToVariant},
+};
+
+use super::activation::ActivationToken;
+use crate::desktop::DesktopEntry;
+
+const APPLICATION_INTERFACE: &str = "org.freedesktop.Application";
+const ACTIVATE_TIMEOUT_MS: i32 = 5000;
+
+pub fn session_bus() -> Option<DBusConnection> {
+    gio::Application::default()
+        .and_then(|app| app.dbus_connection())
+        .or_else(|| gio::bus_get_sync(BusType::Session, gio::Cancellable::NONE).ok())
+}
+
+/// Well-known bus name of a `DBusActivatable=true` entry, which is its desktop
+/// id without the `.desktop` suffix.
+fn bus_name(entry: &DesktopEntry) -> &str {
+    entry.id.strip_suffix(".desktop").unwrap_or(&entry.id)
+}
+
+fn object_path(bus_name: &str) -> String {
+    format!("/{}", bus_name.replace('.', "/").replace('-', "_"))
+}
+
+/// Calls `Activate`, or `ActivateAction` when `action` is set, on the app's
+/// `org.freedesktop.Application` interface. The bus starts the service if it
+/// is not running yet; when that fails `fallback` gets the token back so the
+/// caller can run the Exec line instead. `Open` is never needed because the
+/// launcher does not pass files or URIs.
+pub fn activate<F>(
+    connection: &DBusConnection,
+    entry: &DesktopEntry,
+    action: Option<&str>,
+    token: Option<ActivationToken>,
+    fallback: F,
+) where
+    F: FnOnce(Option<ActivationToken>) + 'static,
+{
+    let bus_name = bus_name(entry).to_string();
+    let path = object_path(&bus_name);
+
+    let platform_data: HashMap<String, glib::Variant> = token
+        .as_ref()
+        .map(|token| {
+            HashMap::from([
+                ("activation-token".to_string(), token.as_str().to_variant()),
+                (
+                    "desktop-startup-id".to_string(),
+                    token.as_str().to_variant(),
+                ),
+            ])
+        })
+        .unwrap_or_default();
+
+    let (method, params) = match action {
+        Some(action) => (
+            "ActivateAction",
+            (action, Vec::<glib::Variant>::new(), platform_data).to_variant(),
+        ),
+        None => ("Activate", (platform_data,).to_variant()),
+    };
+
+    let hold = gio::Application::default().map(|app| app.hold());
+    let name = entry.name.clone();
+    let service = bus_name.clone();
+
+    connection.call(
+        Some(&bus_name),
+        &path,
+        APPLICATION_INTERFACE,
+        method,
+        Some(&params),
+        None,
+        DBusCallFlags::NONE,
+        ACTIVATE_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+        move |result| {
+            let _hold = hold;
+            match result {
+                Ok(_) => println!("Activated over D-Bus: {}", name),
+                Err(e) => {
+                    eprintln!(
+                        "D-Bus activation of {} failed, falling back to Exec: {}",
+                        service, e
+                    );
+                    fallback(token);
+                }
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    fn entry(id: &str) -> DesktopEntry {
+        DesktopEntry {
+            id: id.to_string(),
+            ..DesktopEntry::default()
+        }
+    }
+
+    #[test]
+    fn bus_name_strips_the_desktop_suffix() {
+        assert_eq!(
+            bus_name(&entry("org.gnome.Nautilus.desktop")),
+            "org.gnome.Nautilus"
+        );
+        assert_eq!(bus_name(&entry("org.gnome.Nautilus")), "org.gnome.Nautilus");
+    }
+
+    #[test]
+    fn object_path_follows_the_bus_name() {
+        assert_eq!(object_path("org.gnome.Nautilus"), "/org/gnome/Nautilus");
+        assert_eq!(
+            object_path("org.gnome.font-viewer"),
+            "/org/gnome/font_viewer"
+        );
+    }
+
+    #[test]
+    fn activation_falls_back_to_exec_without_a_service() {
+        let bus = gio::TestDBus::new(gio::TestDBusFlags::NONE);
+        bus.up();
+
+        let context = glib::MainContext::new();
+        let fell_back = context
+            .with_thread_default(|| {
+                let connection = gio::DBusConnection::for_address_sync(
+                    &bus.bus_address().expect("test bus has an address"),
+                    gio::DBusConnectionFlags::AUTHENTICATION_CLIENT
+                        | gio::DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+                    None,
+                    gio::Cancellable::NONE,
+                )
+                .expect("connects to the test bus");
+
+                let fell_back = Rc::new(Cell::new(false));
+                activate(
+                    &connection,
+                    &entry("org.example.Missing.desktop"),
+                    None,
+                    None,
+                    {
+                        let fell_back = fell_back.clone();
+                        move |token| {
+                            assert!(token.is_none());
+                            fell_back.set(true);
+                        }
+                    },
+                );
+                while !fell_back.get() {
+                    context.iteration(true);
+                }
+                fell_back.get()
+            })
+            .expect("context is free");
+        assert!(fell_back);
+
+        bus.down();
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use gtk4::{
+    gio::{self, prelude::*, DBusCallFlags},
+    glib::{self, prelude::ToVariant},
+};
+
+use crate::{config::Config, ui::UiController};
+
+const STDERR_TAIL_LINES: usize = 6;
+const STDERR_TAIL_CHARS: usize = 600;
+const NOTIFY_TIMEOUT_MS: i32 = 2000;
+
+#[derive(Debug, Clone)]
+pub struct LaunchFailure {
+    pub app_name: String,
+    pub icon: Option<String>,
+    pub reason: String,
+    pub stderr_tail: Option<String>,
+}
+
+impl LaunchFailure {
+    pub fn new(app_name: &str, icon: Option<&str>, reason: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+            icon: icon.map(str::to_string),
+            reason: reason.into(),
+            stderr_tail: None,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!("Failed to launch {}", self.app_name)
+    }
+
+    pub fn body(&self) -> String {
+        match &self.stderr_tail {
+            Some(tail) => format!("{}\n\n{}", self.reason, tail),
+            None => self.reason.clone(),
+        }
+    }
+}
+
+impl fmt::Display for LaunchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.summary(), self.reason)
+    }
+}
+
+/// Shows `failure` in the launcher when its window is still open, otherwise
+/// falls back to a desktop notification when those are enabled.
+pub fn report(failure: &LaunchFailure, ui: Option<&UiController>) {
+    eprintln!("{}", failure);
+    if let Some(tail) = &failure.stderr_tail {
+        eprintln!("{}", tail);
+    }
+
+    if ui.is_some_and(|ui| ui.show_error(&failure.summary(), &failure.body())) {
+        return;
+    }
+
+    if Config::load().launch_error_notifications {
+        notify(failure);
+    }
+}
+
+pub fn stderr_tail(log_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(log_path).ok()?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let tail = lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n");
+    let skip = tail.chars().count().saturating_sub(STDERR_TAIL_CHARS);
+    Some(tail.chars().skip(skip).collect())
+}
+
+fn notify(failure: &LaunchFailure) {
+    let Some(connection) = super::dbus::session_bus() else {
+        eprintln!("No session bus available for launch error notification");
+        return;
+    };
+
+    let hints: HashMap<String, glib::Variant> =
+        HashMap::from([("urgency".to_string(), 2u8.to_variant())]);
+
+    let params = (
+        "Hypr Bucket",
+        0u32,
+        failure.icon.as_deref().unwrap_or("dialog-error"),
+        failure.summary(),
+        failure.body(),
+        Vec::<String>::new(),
+        hints,
+        -1i32,
+    )
+        .to_variant();
+
+    // Keep the process alive until the notification daemon has answered.
+    let hold = gio::Application::default().map(|app| app.hold());
+
+    connection.call(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+        "Notify",
+        Some(&params),
+        None,
+        DBusCallFlags::NONE,
+        NOTIFY_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+        move |result| {
+            drop(hold);
+            if let Err(e) = result {
+                eprintln!("Failed to send launch error notification: {}", e);
+            }
+        },
+    );
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use super::{find_executable, shell_quote};
+
+const TERMINALS: &[(&str, &[&str])] = &[
+    ("kitty", &["-e"]),
+    ("alacritty", &["-e"]),
+    ("wezterm", &["start", "--"]),
+    ("foot", &[]),
+    ("ghostty", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("kgx", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("terminator", &["-x"]),
+    ("tilix", &["-e"]),
+    ("st", &["-e"]),
+    ("urxvt", &["-e"]),
+    ("xterm", &["-e"]),
+];
+
+const DEFAULT_ARGS: &[&str] = &["-e"];
+
+#[derive(Debug)]
+pub struct Terminal {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Terminal {
+    /// Picks the configured terminal, then `$TERMINAL`, then `xdg-terminal-exec`,
+    /// then the first installed terminal from the built-in table.
+    pub fn resolve(configured: Option<&str>, args_override: Option<&[String]>) -> Option<Self> {
+        let from_env = std::env::var("TERMINAL").ok();
+
+        let candidate = configured
+            .into_iter()
+            .chain(from_env.as_deref())
+            .map(str::trim)
+            .find(|program| !program.is_empty() && find_executable(program).is_some())
+            .map(str::to_string)
+            .or_else(|| {
+                find_executable("xdg-terminal-exec").map(|_| "xdg-terminal-exec".to_string())
+            })
+            .or_else(|| {
+                TERMINALS
+                    .iter()
+                    .find(|(name, _)| find_executable(name).is_some())
+                    .map(|(name, _)| name.to_string())
+            })?;
+
+        let args = match args_override {
+            Some(args) => args.to_vec(),
+            None => default_args(&candidate),
+        };
+
+        Some(Self {
+            program: candidate,
+            args,
+        })
+    }
+
+    pub fn wrap(&self, command: &str) -> String {
+        let mut parts = vec![shell_quote(&self.program)];
+        parts.extend(self.args.iter().map(|arg| shell_quote(arg)));
+        parts.push("sh".to_string());
+        parts.push("-c".to_string());
+        parts.push(shell_quote(command));
+        parts.join(" ")
+    }
+}
+
+fn default_args(program: &str) -> Vec<String> {
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program);
+
+    if name == "xdg-terminal-exec" {
+        return Vec::new();
+    }
+
+    TERMINALS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, args)| *args)
+        .unwrap_or(DEFAULT_ARGS)
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
@@ -5,6 +5,8 @@ use std::sync::{Arc, Mutex};
 mod app;
 mod config;
 mod desktop;
+mod history;
+mod hyprland;
 mod keybinds;
 mod launcher;
 mod single_instance;
@@ -12,20 +14,89 @@ mod ui;
 
 const APP_ID: &str = "com.github.timeon.hyprbucket";
 
+struct Args {
+    reload_css: bool,
+    theme: Option<String>,
+    monitor: Option<String>,
+    gtk_args: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut parsed = Args {
+        reload_css: false,
+        theme: None,
+        monitor: None,
+        gtk_args: Vec::new(),
+    };
+
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--reload-css" {
+            parsed.reload_css = true;
+        } else if arg == "--theme" {
+            parsed.theme = Some(args.next().ok_or("--theme needs a theme name")?);
+        } else if let Some(theme) = arg.strip_prefix("--theme=") {
+            parsed.theme = Some(theme.to_string());
+        } else if arg == "--monitor" {
+            parsed.monitor = Some(args.next().ok_or("--monitor needs a monitor name")?);
+        } else if let Some(monitor) = arg.strip_prefix("--monitor=") {
+            parsed.monitor = Some(monitor.to_string());
+        } else {
+            parsed.gtk_args.push(arg);
+        }
+    }
+
+    Ok(parsed)
+}
+
 fn main() -> gtk4::glib::ExitCode {
-    if single_instance::notify_existing_instance() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return gtk4::glib::ExitCode::FAILURE;
+        }
+    };
+
+    if args.reload_css {
+        if single_instance::send_commands(&[single_instance::RELOAD_CSS.to_string()]) {
+            return gtk4::glib::ExitCode::SUCCESS;
+        }
+        eprintln!("hyprbucket is not running");
+        return gtk4::glib::ExitCode::FAILURE;
+    }
+
+    let mut commands = Vec::new();
+    if let Some(theme) = &args.theme {
+        commands.push(format!("{} {}", single_instance::SET_THEME, theme));
+    }
+    if let Some(monitor) = &args.monitor {
+        commands.push(format!("{} {}", single_instance::OPEN_ON_MONITOR, monitor));
+    }
+
+    let existing_instance = if commands.is_empty() {
+        single_instance::notify_existing_instance()
+    } else {
+        single_instance::send_commands(&commands)
+    };
+    if existing_instance {
         return gtk4::glib::ExitCode::SUCCESS;
     }
 
+    if let Some(theme) = &args.theme {
+        app::styles::set_theme(theme);
+    }
+
     let app = Application::builder().application_id(APP_ID).build();
 
     let app_ref = Arc::new(Mutex::new(Some(app.clone())));
     let app_ref_clone = app_ref.clone();
 
+    let monitor = args.monitor;
     app.connect_activate(move |app| {
         single_instance::setup_socket_listener(app_ref_clone.clone());
-        app::build_ui(app);
+        app::build_ui(app, monitor.as_deref());
     });
 
-    app.run()
+    app.run_with_args(&args.gtk_args)
 }
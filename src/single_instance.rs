@@ -1,13 +1,15 @@
 use std::fs;
+use std::io::{Read, Write};
 use std::os::unix::io::AsRawFd;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 use gtk4::Application;
 use gtk4::glib;
 use gtk4::prelude::{GtkApplicationExt, GtkWindowExt};
 
-fn get_socket_path() -> PathBuf {
+pub fn runtime_dir() -> PathBuf {
     let xdg_runtime = std::env::var("XDG_RUNTIME_DIR")
         .unwrap_or_else(|_| format!("/tmp/hypr-bucket-{}", unsafe { libc::getuid() }));
 
@@ -16,10 +18,14 @@ fn get_socket_path() -> PathBuf {
     path
 }
 
+pub const RELOAD_CSS: &str = "reload-css";
+pub const SET_THEME: &str = "theme";
+pub const OPEN_ON_MONITOR: &str = "monitor";
+
 pub fn notify_existing_instance() -> bool {
     use std::os::unix::net::UnixStream;
 
-    let mut socket_path = get_socket_path();
+    let mut socket_path = runtime_dir();
     socket_path.push("hyprbucket.sock");
 
     if let Ok(_stream) = UnixStream::connect(&socket_path) {
@@ -29,10 +35,50 @@ pub fn notify_existing_instance() -> bool {
     false
 }
 
+/// Sends `commands` to the running instance, one per line, returning false
+/// when there is none. A connection without commands toggles the launcher window.
+pub fn send_commands(commands: &[String]) -> bool {
+    use std::os::unix::net::UnixStream;
+
+    let mut socket_path = runtime_dir();
+    socket_path.push("hyprbucket.sock");
+
+    match UnixStream::connect(&socket_path) {
+        Ok(mut stream) => {
+            if let Err(e) = writeln!(stream, "{}", commands.join("\n")) {
+                eprintln!("Failed to send command: {}", e);
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn read_commands(stream: std::os::unix::net::UnixStream) -> Vec<String> {
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(200)));
+
+    let mut commands = String::new();
+    let _ = stream.take(1024).read_to_string(&mut commands);
+    commands
+        .lines()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn argument<'a>(command: &'a str, name: &str) -> Option<&'a str> {
+    command
+        .strip_prefix(name)
+        .and_then(|rest| rest.strip_prefix(' '))
+        .map(str::trim)
+        .filter(|argument| !argument.is_empty())
+}
+
 pub fn setup_socket_listener(app: Arc<Mutex<Option<Application>>>) {
     use std::os::unix::net::UnixListener;
 
-    let socket_dir = get_socket_path();
+    let socket_dir = runtime_dir();
     let socket_path = {
         let mut p = socket_dir.clone();
         p.push("hyprbucket.sock");
@@ -56,10 +102,33 @@ pub fn setup_socket_listener(app: Arc<Mutex<Option<Application>>>) {
     glib::unix_fd_add_local(fd, glib::IOCondition::IN, move |_fd, condition| {
         if condition.contains(glib::IOCondition::IN) {
             if let Ok((stream, _)) = listener.accept() {
-                drop(stream);
+                let commands = read_commands(stream);
+                if commands.iter().any(|command| command == RELOAD_CSS) {
+                    crate::app::styles::reload();
+                    return glib::ControlFlow::Continue;
+                }
+
+                let theme = commands
+                    .iter()
+                    .find_map(|command| argument(command, SET_THEME));
+                let monitor = commands
+                    .iter()
+                    .find_map(|command| argument(command, OPEN_ON_MONITOR));
+                if let Some(name) = theme {
+                    crate::app::styles::set_theme(name);
+                    crate::app::styles::reload();
+                }
+
                 if let Ok(app_lock) = app.lock() {
                     if let Some(app) = app_lock.as_ref() {
-                        app.active_window().map(|w| w.close());
+                        // The process can outlive its window while launches are still being
+                        // watched, in which case a new invocation opens the launcher again.
+                        // Switching themes keeps an open launcher open.
+                        match app.active_window() {
+                            Some(_) if theme.is_some() => {}
+                            Some(window) => window.close(),
+                            None => crate::app::build_ui(app, monitor),
+                        }
                     }
                 }
             }
@@ -1,5 +1,5 @@
 use std::{
-    cell::{Cell, RefCell},
+    cell::{Cell, OnceCell, RefCell},
     collections::{HashMap, HashSet, VecDeque},
     rc::Rc,
     time::Duration,
@@ -7,44 +7,73 @@ use std::{
 
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use gtk4::{
-    gio::ListStore,
+    gdk,
+    gio::{self, ListStore},
     glib::{self, Object},
     prelude::*,
     subclass::prelude::*,
-    Box, CustomFilter, CustomSorter, Entry, FilterChange, FilterListModel, GridView, ListItem,
-    ListScrollFlags, Orientation, ScrolledWindow, SignalListItemFactory, SingleSelection,
-    SortListModel, SorterChange,
+    Box, CustomFilter, CustomSorter, DragSource, DropTarget, Entry, FilterChange, FilterListModel,
+    GestureClick, GridView, Label, ListItem, ListScrollFlags, Orientation, ScrolledWindow,
+    SignalListItemFactory, SingleSelection, SortListModel, SorterChange,
 };
 
+pub mod categories;
 pub mod components;
-pub use components::{create_app_row, populate_app_row};
+pub mod context_menu;
+pub mod details;
+pub mod icons;
+pub mod pins;
+pub use components::{create_app_row, create_app_tile, populate_app_row, Layout, RowState};
+use details::DetailsPanel;
+use pins::Pins;
 
 use crate::{
     config::Config,
-    desktop::{DesktopEntry, LoaderMsg},
+    desktop::{DesktopEntry, LoaderMsg, MainCategory, CUSTOM_ID_PREFIX},
+    keybinds::{QuickLaunch, VimMode},
 };
 
 const SCORE_NO_MATCH: i64 = i64::MIN;
 const BATCH_CHUNK: usize = 150;
-const SAVE_PINS_DEBOUNCE_MS: u64 = 200;
+const SAVE_DEBOUNCE_MS: u64 = 200;
+const KEYWORD_SCORE_DIVISOR: i64 = 2;
+const SEARCH_PLACEHOLDER: &str = "Search applications...";
+const HIDDEN_PLACEHOLDER: &str = "Search hidden applications...";
+const QUICK_LAUNCH_SLOTS: u32 = 9;
 
 type ObjById = Rc<RefCell<HashMap<String, AppEntryObject>>>;
+type BoundRows = Rc<RefCell<Vec<glib::WeakRef<ListItem>>>>;
 
 #[derive(Clone)]
 pub struct UiController {
     base: ListStore,
     by_id: ObjById,
     query: Rc<RefCell<String>>,
-    pinned: Rc<RefCell<HashSet<String>>>,
+    pinned: Rc<RefCell<Pins>>,
     filter: CustomFilter,
     sorter: CustomSorter,
     grid_view: glib::WeakRef<GridView>,
+    rows: BoundRows,
     selection_guard: Rc<Cell<bool>>,
-    pins_save_source: Rc<RefCell<Option<glib::SourceId>>>,
+    save_source: Rc<RefCell<Option<glib::SourceId>>>,
+    running_classes: Rc<Vec<String>>,
+    error_banner: glib::WeakRef<Box>,
+    hidden: Rc<RefCell<HashSet<String>>>,
+    show_hidden: Rc<Cell<bool>>,
+    search_entry: glib::WeakRef<Entry>,
+    mode_label: glib::WeakRef<Label>,
+    multi_label: glib::WeakRef<Label>,
+    multi_select: Rc<Cell<bool>>,
+    marked: Rc<RefCell<Vec<String>>>,
+    category: Rc<Cell<Option<MainCategory>>>,
+    category_bar: glib::WeakRef<Box>,
+    config: Rc<Config>,
 }
 
 impl UiController {
     pub fn set_query(&self, new_query: String) {
+        self.hide_error();
+
         let was_empty = self.query.borrow().is_empty();
 
         let prev_selected_id = self.selected_app_id();
@@ -56,46 +85,323 @@ impl UiController {
         self.sorter.changed(SorterChange::Different);
 
         if new_query.is_empty() && !was_empty {
-            if let Some(grid_view) = self.grid_view.upgrade() {
-                if let Some(model) = grid_view.model() {
-                    if let Some(selection) = model.downcast_ref::<SingleSelection>() {
-                        if selection.n_items() > 0 {
-                            self.selection_guard.set(true);
-                            selection.set_selected(0);
-                            self.selection_guard.set(false);
-                            grid_view.scroll_to(0, ListScrollFlags::NONE, None);
-                        }
-                    }
-                }
-            }
+            self.select_first();
             return;
         }
 
         self.reselect_by_id(prev_selected_id);
     }
 
+    /// Shows an error banner above the results, returning `false` when the
+    /// launcher window is no longer on screen.
+    pub fn show_error(&self, title: &str, body: &str) -> bool {
+        let Some(banner) = self.error_banner.upgrade() else {
+            return false;
+        };
+        if !banner.root().is_some_and(|root| root.is_mapped()) {
+            return false;
+        }
+
+        let title_label = banner.first_child().and_downcast::<Label>();
+        let body_label = title_label
+            .as_ref()
+            .and_then(|label| label.next_sibling())
+            .and_downcast::<Label>();
+
+        if let (Some(title_label), Some(body_label)) = (title_label, body_label) {
+            title_label.set_text(title);
+            body_label.set_text(body);
+            banner.set_visible(true);
+            return true;
+        }
+
+        false
+    }
+
+    pub fn hide_error(&self) {
+        if let Some(banner) = self.error_banner.upgrade() {
+            banner.set_visible(false);
+        }
+    }
+
     pub fn pinned_snapshot(&self) -> HashSet<String> {
-        self.pinned.borrow().iter().cloned().collect()
+        self.pinned.borrow().order().iter().cloned().collect()
     }
 
     pub fn toggle_pin(&self, app_id: &str) -> bool {
+        let now_pinned = self.pinned.borrow_mut().toggle(app_id);
+        self.pins_changed();
+        now_pinned
+    }
+
+    pub fn move_selected_pin(&self, delta: i32) -> bool {
+        let Some(id) = self.selected_app_id() else {
+            return false;
+        };
+
+        let moved = self.pinned.borrow_mut().move_by(&id, delta);
+        if moved {
+            self.pins_changed();
+        }
+        moved
+    }
+
+    pub fn move_pin_onto(&self, id: &str, target: &str) -> bool {
+        let moved = self.pinned.borrow_mut().move_onto(id, target);
+        if moved {
+            self.pins_changed();
+        }
+        moved
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn has_query(&self) -> bool {
+        !self.query.borrow().is_empty()
+    }
+
+    pub fn quick_launch(&self) -> QuickLaunch {
+        self.config.quick_launch
+    }
+
+    pub fn columns(&self) -> u32 {
+        columns_for(&self.config)
+    }
+
+    pub fn grid_view(&self) -> Option<GridView> {
+        self.grid_view.upgrade()
+    }
+
+    pub fn is_hidden(&self, id: &str) -> bool {
+        self.hidden.borrow().contains(id)
+    }
+
+    pub fn is_pinned(&self, id: &str) -> bool {
+        self.pinned.borrow().contains(id)
+    }
+
+    pub fn pin_section(&self, id: &str) -> Option<String> {
+        let pinned = self.pinned.borrow();
+        if !pinned.contains(id) {
+            return None;
+        }
+        pinned.group(id).map(str::to_string)
+    }
+
+    fn pins_changed(&self) {
         let prev_selected_id = self.selected_app_id();
 
-        let now_pinned = {
-            let mut pinned = self.pinned.borrow_mut();
-            if pinned.remove(app_id) {
+        self.request_save();
+        self.sorter.changed(SorterChange::Different);
+        self.refresh_rows();
+        self.reselect_by_id(prev_selected_id);
+    }
+
+    /// Rebinds the rows on screen, as marks and group headers are not part
+    /// of the model and headers depend on the neighbouring rows.
+    fn refresh_rows(&self) {
+        let Some(selection) = self
+            .grid_view
+            .upgrade()
+            .and_then(|grid_view| grid_view.model())
+            .and_downcast::<SingleSelection>()
+        else {
+            return;
+        };
+
+        self.rows.borrow_mut().retain(|row| match row.upgrade() {
+            Some(list_item) => {
+                if list_item.item().is_some() {
+                    bind_row(&list_item, &selection, Some(self));
+                }
+                true
+            }
+            None => false,
+        });
+    }
+
+    pub fn toggle_hidden_selected(&self) -> Option<(String, bool)> {
+        let id = self.selected_app_id()?;
+        let name = self.by_id.borrow().get(&id)?.name_ref().to_string();
+        Some((name, self.toggle_hidden(&id)))
+    }
+
+    pub fn toggle_hidden(&self, id: &str) -> bool {
+        let now_hidden = {
+            let mut hidden = self.hidden.borrow_mut();
+            if hidden.remove(id) {
                 false
             } else {
-                pinned.insert(app_id.to_string());
+                hidden.insert(id.to_string());
                 true
             }
         };
 
-        self.request_save_pins();
-        self.sorter.changed(SorterChange::Different);
-        self.reselect_by_id(prev_selected_id);
+        self.request_save();
+        self.filter.changed(FilterChange::Different);
 
-        now_pinned
+        now_hidden
+    }
+
+    pub fn toggle_hidden_view(&self) -> bool {
+        let show_hidden = !self.show_hidden.get();
+        self.show_hidden.set(show_hidden);
+
+        if let Some(search_entry) = self.search_entry.upgrade() {
+            search_entry.set_placeholder_text(Some(if show_hidden {
+                HIDDEN_PLACEHOLDER
+            } else {
+                SEARCH_PLACEHOLDER
+            }));
+        }
+
+        self.filter.changed(FilterChange::Different);
+        self.select_first();
+
+        show_hidden
+    }
+
+    pub fn set_category(&self, category: Option<MainCategory>) {
+        if self.category.get() == category {
+            return;
+        }
+        self.category.set(category);
+
+        if let Some(bar) = self.category_bar.upgrade() {
+            categories::activate_tab(&bar, category);
+        }
+        self.filter.changed(FilterChange::Different);
+        self.select_first();
+    }
+
+    pub fn cycle_category(&self, delta: i32) {
+        let Some(bar) = self.category_bar.upgrade().filter(|bar| bar.is_visible()) else {
+            return;
+        };
+        self.set_category(categories::cycle(&bar, self.category.get(), delta));
+    }
+
+    fn refresh_categories(&self) {
+        let Some(bar) = self.category_bar.upgrade() else {
+            return;
+        };
+
+        let present: Vec<MainCategory> = {
+            let by_id = self.by_id.borrow();
+            MainCategory::ALL
+                .into_iter()
+                .filter(|category| {
+                    by_id
+                        .values()
+                        .any(|obj| obj.entry_ref().in_category(*category))
+                })
+                .collect()
+        };
+
+        if !categories::update_tabs(&bar, &present) {
+            self.set_category(None);
+        }
+    }
+
+    pub fn set_vim_mode(&self, mode: VimMode) {
+        if let Some(mode_label) = self.mode_label.upgrade() {
+            mode_label.set_text(mode.label());
+        }
+
+        if let Some(search_entry) = self.search_entry.upgrade() {
+            if mode == VimMode::Normal {
+                search_entry.add_css_class("normal-mode");
+            } else {
+                search_entry.remove_css_class("normal-mode");
+            }
+        }
+    }
+
+    pub fn is_multi_select(&self) -> bool {
+        self.multi_select.get()
+    }
+
+    pub fn toggle_multi_select(&self) {
+        let enabled = !self.multi_select.get();
+        self.multi_select.set(enabled);
+        if !enabled {
+            self.marked.borrow_mut().clear();
+        }
+
+        self.update_multi_label();
+        self.refresh_rows();
+    }
+
+    pub fn is_marked(&self, id: &str) -> bool {
+        self.marked.borrow().iter().any(|marked| marked == id)
+    }
+
+    pub fn toggle_mark_selected(&self) -> Option<(String, bool)> {
+        if !self.multi_select.get() {
+            return None;
+        }
+
+        let id = self.selected_app_id()?;
+        let name = self.by_id.borrow().get(&id)?.name_ref().to_string();
+
+        let now_marked = {
+            let mut marked = self.marked.borrow_mut();
+            match marked.iter().position(|marked| *marked == id) {
+                Some(index) => {
+                    marked.remove(index);
+                    false
+                }
+                None => {
+                    marked.push(id);
+                    true
+                }
+            }
+        };
+
+        self.update_multi_label();
+        self.refresh_rows();
+        Some((name, now_marked))
+    }
+
+    pub fn take_marked(&self) -> Vec<DesktopEntry> {
+        let ids = std::mem::take(&mut *self.marked.borrow_mut());
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let entries = {
+            let by_id = self.by_id.borrow();
+            ids.iter()
+                .filter_map(|id| by_id.get(id).map(AppEntryObject::entry))
+                .collect()
+        };
+
+        self.update_multi_label();
+        self.refresh_rows();
+        entries
+    }
+
+    fn update_multi_label(&self) {
+        let Some(multi_label) = self.multi_label.upgrade() else {
+            return;
+        };
+
+        multi_label.set_visible(self.multi_select.get());
+        multi_label.set_text(&format!("MULTI · {} marked", self.marked.borrow().len()));
+    }
+
+    pub fn focus_search(&self, clear: bool) {
+        let Some(search_entry) = self.search_entry.upgrade() else {
+            return;
+        };
+
+        if clear {
+            search_entry.set_text("");
+        }
+        search_entry.grab_focus();
+        search_entry.set_position(-1);
     }
 
     pub fn cleanup_stale_pins(&self) {
@@ -103,43 +409,60 @@ impl UiController {
 
         let changed = {
             let mut pinned = self.pinned.borrow_mut();
-            let before = pinned.len();
-            pinned.retain(|id| existing.contains(id));
-            pinned.len() != before
+            pinned.retain(|id| existing.contains(id))
         };
 
         if changed {
-            self.request_save_pins();
+            self.request_save();
             self.sorter.changed(SorterChange::Different);
         }
     }
 
-    fn request_save_pins(&self) {
-        if let Some(old) = self.pins_save_source.borrow_mut().take() {
+    fn request_save(&self) {
+        if let Some(old) = self.save_source.borrow_mut().take() {
             old.remove();
         }
 
-        let pinned_vec: Vec<String> = self.pinned.borrow().iter().cloned().collect();
+        let (pinned_vec, groups) = {
+            let pinned = self.pinned.borrow();
+            (pinned.order().to_vec(), pinned.groups().clone())
+        };
+        let mut hidden_vec: Vec<String> = self.hidden.borrow().iter().cloned().collect();
+        hidden_vec.sort();
 
-        let mut pinned_opt = Some(pinned_vec);
+        let mut state_opt = Some((pinned_vec, groups, hidden_vec));
 
         let source_id =
-            glib::timeout_add_local(Duration::from_millis(SAVE_PINS_DEBOUNCE_MS), move || {
-                if let Some(pins) = pinned_opt.take() {
-                    Config { pinned: pins }.save();
+            glib::timeout_add_local(Duration::from_millis(SAVE_DEBOUNCE_MS), move || {
+                if let Some((pins, groups, hidden)) = state_opt.take() {
+                    if let Some(mut config) = Config::load_for_update() {
+                        config.pinned = pins;
+                        config.pin_groups = groups;
+                        config.hidden = hidden;
+                        config.save();
+                    }
                 }
                 glib::ControlFlow::Break
             });
 
-        *self.pins_save_source.borrow_mut() = Some(source_id);
+        *self.save_source.borrow_mut() = Some(source_id);
     }
 
-    pub fn upsert_entry(&self, entry: DesktopEntry) {
+    pub fn upsert_entry(&self, mut entry: DesktopEntry) {
+        if let Some(app) = self.config.app(&entry.id) {
+            app.apply(&mut entry);
+        }
+
         let id = entry.id.clone();
 
         let query = self.query.borrow().clone();
-        let score = compute_score(&entry.name, &query);
+        let score = compute_score(&entry.name, &entry.keywords, &query);
+        let running = self
+            .running_classes
+            .iter()
+            .any(|class| entry.matches_window_class(class));
         let obj = AppEntryObject::new(entry, score);
+        obj.set_running(running);
 
         if self.by_id.borrow().contains_key(&id) {
             if let Some(idx) = find_in_base(&self.base, &id) {
@@ -155,6 +478,49 @@ impl UiController {
         self.by_id.borrow_mut().insert(id, obj);
     }
 
+    /// Replaces the entries defined in config.toml, leaving unchanged ones
+    /// alone so their rows and the selection are kept.
+    pub fn set_custom_entries(&self, entries: Vec<DesktopEntry>) {
+        let wanted: HashSet<&str> = entries.iter().map(|entry| entry.id.as_str()).collect();
+        let stale: Vec<String> = self
+            .by_id
+            .borrow()
+            .keys()
+            .filter(|id| id.starts_with(CUSTOM_ID_PREFIX) && !wanted.contains(id.as_str()))
+            .cloned()
+            .collect();
+
+        // Pins of removed entries are kept until the next full load, in case
+        // the entry only disappeared while config.toml was being edited.
+        for id in &stale {
+            self.remove_object(id);
+        }
+
+        for entry in entries {
+            let mut shown = entry.clone();
+            if let Some(app) = self.config.app(&entry.id) {
+                app.apply(&mut shown);
+            }
+
+            let unchanged = self
+                .by_id
+                .borrow()
+                .get(&entry.id)
+                .is_some_and(|obj| *obj.entry_ref() == shown);
+            if !unchanged {
+                self.upsert_entry(entry);
+            }
+        }
+        self.refresh_categories();
+    }
+
+    fn remove_object(&self, id: &str) {
+        self.by_id.borrow_mut().remove(id);
+        if let Some(idx) = find_in_base(&self.base, id) {
+            self.base.remove(idx);
+        }
+    }
+
     pub fn remove_ids<I>(&self, ids: I)
     where
         I: IntoIterator<Item = String>,
@@ -162,17 +528,14 @@ impl UiController {
         let mut pins_changed = false;
 
         for id in ids {
-            self.by_id.borrow_mut().remove(&id);
-            if let Some(idx) = find_in_base(&self.base, &id) {
-                self.base.remove(idx);
-            }
+            self.remove_object(&id);
             if self.pinned.borrow_mut().remove(&id) {
                 pins_changed = true;
             }
         }
 
         if pins_changed {
-            self.request_save_pins();
+            self.request_save();
             self.sorter.changed(SorterChange::Different);
         }
     }
@@ -191,16 +554,82 @@ impl UiController {
             let score = if query.is_empty() {
                 0
             } else {
-                matcher
-                    .fuzzy_match(&*obj.name_ref(), query)
-                    .unwrap_or(SCORE_NO_MATCH)
+                let entry = obj.entry_ref();
+                match_score(&matcher, &entry.name, &entry.keywords, query)
             };
 
             obj.set_score(score);
         }
     }
 
-    fn selected_app_id(&self) -> Option<String> {
+    fn select_first(&self) {
+        if let Some(grid_view) = self.grid_view.upgrade() {
+            if let Some(model) = grid_view.model() {
+                if let Some(selection) = model.downcast_ref::<SingleSelection>() {
+                    if selection.n_items() > 0 {
+                        self.selection_guard.set(true);
+                        selection.set_selected(0);
+                        self.selection_guard.set(false);
+                        grid_view.scroll_to(0, ListScrollFlags::NONE, None);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<DesktopEntry> {
+        let grid_view = self.grid_view.upgrade()?;
+        let selection = grid_view.model().and_downcast::<SingleSelection>()?;
+        let obj = selection.selected_item().and_downcast::<AppEntryObject>()?;
+        Some(obj.entry())
+    }
+
+    fn select_position(&self, position: u32) {
+        let Some(grid_view) = self.grid_view.upgrade() else {
+            return;
+        };
+        if let Some(selection) = grid_view.model().and_downcast::<SingleSelection>() {
+            if position < selection.n_items() {
+                selection.set_selected(position);
+            }
+        }
+    }
+
+    pub fn close_window(&self) {
+        if let Some(window) = self
+            .grid_view
+            .upgrade()
+            .and_then(|grid_view| grid_view.root())
+            .and_downcast::<gtk4::Window>()
+        {
+            window.close();
+        }
+    }
+
+    pub fn show_context_menu(&self) -> bool {
+        let Some(entry) = self.selected_entry() else {
+            return false;
+        };
+        let Some(grid_view) = self.grid_view.upgrade() else {
+            return false;
+        };
+
+        let mut selected_row = None;
+        let mut child = grid_view.first_child();
+        while let Some(widget) = child {
+            if widget.state_flags().contains(gtk4::StateFlags::SELECTED) {
+                selected_row = widget.first_child();
+                break;
+            }
+            child = widget.next_sibling();
+        }
+
+        let parent = selected_row.unwrap_or_else(|| grid_view.upcast());
+        context_menu::show(self, entry, &parent, None);
+        true
+    }
+
+    pub fn selected_app_id(&self) -> Option<String> {
         let grid_view = self.grid_view.upgrade()?;
         let model = grid_view.model()?;
         let selection = model.downcast_ref::<SingleSelection>()?;
@@ -247,7 +676,7 @@ impl UiController {
 
 pub fn build_content() -> (Box, UiController) {
     let selection_guard = Rc::new(Cell::new(false));
-    let pins_save_source = Rc::new(RefCell::new(None));
+    let save_source = Rc::new(RefCell::new(None));
 
     let container = Box::new(Orientation::Vertical, 0);
     container.set_hexpand(false);
@@ -257,23 +686,53 @@ pub fn build_content() -> (Box, UiController) {
     let (search_box, search_entry) = create_search_box();
     container.append(&search_box);
 
+    let error_banner = create_error_banner();
+    container.append(&error_banner);
+
+    let category_bar = categories::create_category_bar();
+    container.append(&category_bar);
+
     let base = ListStore::new::<AppEntryObject>();
     let by_id: ObjById = Rc::new(RefCell::new(HashMap::new()));
 
+    let config = Rc::new(Config::load());
+    category_bar.set_visible(config.show_categories);
     let query: Rc<RefCell<String>> = Rc::new(RefCell::new(String::new()));
-    let pinned: Rc<RefCell<HashSet<String>>> =
-        Rc::new(RefCell::new(Config::load().pinned.into_iter().collect()));
+    let pinned = Rc::new(RefCell::new(Pins::new(
+        config.pinned.clone(),
+        config.pin_groups.clone(),
+    )));
+    let hidden: Rc<RefCell<HashSet<String>>> =
+        Rc::new(RefCell::new(config.hidden.iter().cloned().collect()));
+    let show_hidden = Rc::new(Cell::new(false));
+    let running_classes = Rc::new(running_window_classes());
+
+    let category: Rc<Cell<Option<MainCategory>>> = Rc::new(Cell::new(None));
 
     let filter = CustomFilter::new({
         let query = query.clone();
+        let hidden = hidden.clone();
+        let show_hidden = show_hidden.clone();
+        let category = category.clone();
         move |obj| {
+            let entry = obj
+                .downcast_ref::<AppEntryObject>()
+                .expect("AppEntryObject expected");
+
+            if hidden.borrow().contains(&*entry.id_ref()) != show_hidden.get() {
+                return false;
+            }
+
+            if let Some(category) = category.get() {
+                if !entry.entry_ref().in_category(category) {
+                    return false;
+                }
+            }
+
             let q = query.borrow();
             if q.is_empty() {
                 return true;
             }
-            let entry = obj
-                .downcast_ref::<AppEntryObject>()
-                .expect("AppEntryObject expected");
             entry.score() != SCORE_NO_MATCH
         }
     });
@@ -292,17 +751,16 @@ pub fn build_content() -> (Box, UiController) {
                 .downcast_ref::<AppEntryObject>()
                 .expect("AppEntryObject expected");
 
-            let pinned_set = pinned.borrow();
-            let ap = pinned_set.contains(&*a.id_ref());
-            let bp = pinned_set.contains(&*b.id_ref());
-            drop(pinned_set);
-
-            match bp.cmp(&ap) {
-                std::cmp::Ordering::Equal => {
-                    if ap {
-                        return a.name_key_ref().cmp(&*b.name_key_ref()).into();
-                    }
+            let pins = pinned.borrow();
+            let a_rank = pins.rank(&a.id_ref());
+            let b_rank = pins.rank(&b.id_ref());
+            drop(pins);
 
+            match (a_rank, b_rank) {
+                (Some(a_rank), Some(b_rank)) => a_rank.cmp(&b_rank).into(),
+                (Some(_), None) => std::cmp::Ordering::Less.into(),
+                (None, Some(_)) => std::cmp::Ordering::Greater.into(),
+                (None, None) => {
                     if query.borrow().is_empty() {
                         a.name_key_ref().cmp(&*b.name_key_ref()).into()
                     } else {
@@ -312,7 +770,6 @@ pub fn build_content() -> (Box, UiController) {
                             .into()
                     }
                 }
-                other => other.into(),
             }
         }
     });
@@ -322,7 +779,16 @@ pub fn build_content() -> (Box, UiController) {
     let selection = SingleSelection::new(Some(sorted));
     selection.set_autoselect(true);
 
-    let grid_view = create_virtual_list(&selection);
+    let ui_cell: Rc<OnceCell<UiController>> = Rc::new(OnceCell::new());
+    let details = config.show_details.then(DetailsPanel::new);
+    let rows = BoundRows::default();
+    let grid_view = create_virtual_list(
+        &selection,
+        &config,
+        details.clone(),
+        rows.clone(),
+        ui_cell.clone(),
+    );
 
     let scrolled = ScrolledWindow::new();
     scrolled.set_vexpand(true);
@@ -330,9 +796,15 @@ pub fn build_content() -> (Box, UiController) {
     scrolled.set_min_content_height(400);
     scrolled.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
     scrolled.set_child(Some(&grid_view));
-    container.append(&scrolled);
 
-    let status_bar = create_status_bar();
+    let body = Box::new(Orientation::Horizontal, 0);
+    body.append(&scrolled);
+    if let Some(details) = &details {
+        body.append(details.widget());
+    }
+    container.append(&body);
+
+    let (status_bar, mode_label, multi_label) = create_status_bar(config.vim_mode);
     container.append(&status_bar);
 
     let ui = UiController {
@@ -343,11 +815,29 @@ pub fn build_content() -> (Box, UiController) {
         filter,
         sorter,
         grid_view: grid_view.downgrade(),
+        rows,
         selection_guard,
-        pins_save_source,
+        save_source,
+        running_classes,
+        error_banner: error_banner.downgrade(),
+        hidden,
+        show_hidden,
+        search_entry: search_entry.downgrade(),
+        mode_label: mode_label.downgrade(),
+        multi_label: multi_label.downgrade(),
+        multi_select: Rc::new(Cell::new(false)),
+        marked: Rc::new(RefCell::new(Vec::new())),
+        category,
+        category_bar: category_bar.downgrade(),
+        config,
     };
 
+    let _ = ui_cell.set(ui.clone());
+
     setup_search(&search_entry, ui.clone());
+    categories::setup_category_bar(&category_bar, ui.clone());
+    setup_activation(&grid_view, ui.clone());
+    setup_config_reload(&container, ui.clone());
 
     glib::idle_add_local_once({
         let ui = ui.clone();
@@ -365,7 +855,7 @@ fn create_search_box() -> (Box, Entry) {
     container.set_margin_end(16);
 
     let search = Entry::new();
-    search.set_placeholder_text(Some("Search applications..."));
+    search.set_placeholder_text(Some(SEARCH_PLACEHOLDER));
     search.add_css_class("search-input");
     search.set_hexpand(true);
 
@@ -373,7 +863,30 @@ fn create_search_box() -> (Box, Entry) {
     (container, search)
 }
 
-fn create_status_bar() -> Box {
+fn create_error_banner() -> Box {
+    let banner = Box::new(Orientation::Vertical, 4);
+    banner.set_margin_bottom(8);
+    banner.set_margin_start(16);
+    banner.set_margin_end(16);
+    banner.add_css_class("error-banner");
+    banner.set_visible(false);
+
+    let title = Label::new(None);
+    title.set_halign(gtk4::Align::Start);
+    title.add_css_class("error-title");
+    banner.append(&title);
+
+    let body = Label::new(None);
+    body.set_halign(gtk4::Align::Start);
+    body.set_wrap(true);
+    body.set_selectable(true);
+    body.add_css_class("error-body");
+    banner.append(&body);
+
+    banner
+}
+
+fn create_status_bar(vim_mode: bool) -> (Box, Label, Label) {
     let status_bar = Box::new(Orientation::Horizontal, 8);
     status_bar.set_margin_top(8);
     status_bar.set_margin_bottom(12);
@@ -381,56 +894,213 @@ fn create_status_bar() -> Box {
     status_bar.set_margin_end(16);
     status_bar.add_css_class("status-bar");
 
+    let mode_label = Label::new(Some(VimMode::Insert.label()));
+    mode_label.add_css_class("mode-indicator");
+    mode_label.set_visible(vim_mode);
+    status_bar.append(&mode_label);
+
+    let multi_label = Label::new(None);
+    multi_label.add_css_class("mode-indicator");
+    multi_label.set_visible(false);
+    status_bar.append(&multi_label);
+
     let status_label = gtk4::Label::new(Some(
-        "↑↓ Navigate  •  Enter to launch  •  Esc to close   •  Ctrl+p Pin",
+        "↑↓ Navigate  •  Enter to launch  •  Esc to close   •  Ctrl+p Pin  •  Ctrl+h Hide",
     ));
     status_label.add_css_class("status-label");
 
     status_bar.append(&status_label);
-    status_bar
+    (status_bar, mode_label, multi_label)
 }
 
-fn create_virtual_list(selection: &SingleSelection) -> GridView {
+fn create_virtual_list(
+    selection: &SingleSelection,
+    config: &Config,
+    details: Option<DetailsPanel>,
+    rows: BoundRows,
+    ui_cell: Rc<OnceCell<UiController>>,
+) -> GridView {
     let factory = SignalListItemFactory::new();
-
-    factory.connect_setup(move |_, list_item| {
-        let row = create_app_row();
-        list_item
-            .downcast_ref::<ListItem>()
-            .expect("ListItem expected")
-            .set_child(Some(&row));
+    let layout = config.layout;
+
+    factory.connect_setup({
+        let ui_cell = ui_cell.clone();
+        let grid = config.grid.clone();
+        let selection = selection.clone();
+        move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("ListItem expected");
+
+            let row = match layout {
+                Layout::List => create_app_row(),
+                Layout::Grid => create_app_tile(&grid),
+            };
+            setup_pin_drag(&row, list_item, ui_cell.clone());
+            setup_row_menu(&row, list_item, ui_cell.clone());
+            list_item.set_child(Some(&row));
+            rows.borrow_mut().push(list_item.downgrade());
+
+            // Badges and headers depend on the position, which can change
+            // without a rebind when rows before this one are filtered out.
+            let ui_cell = ui_cell.clone();
+            let selection = selection.clone();
+            list_item.connect_position_notify(move |list_item| {
+                if list_item.item().is_some() {
+                    bind_row(list_item, &selection, ui_cell.get());
+                }
+            });
+        }
     });
 
-    factory.connect_bind(move |_, list_item| {
-        let list_item = list_item
-            .downcast_ref::<ListItem>()
-            .expect("ListItem expected");
-
-        let entry_obj = list_item
-            .item()
-            .and_then(|item| item.downcast::<AppEntryObject>().ok())
-            .expect("AppEntryObject expected");
-
-        let row = list_item
-            .child()
-            .and_downcast::<Box>()
-            .expect("Box expected");
-        populate_app_row(&row, &entry_obj);
+    factory.connect_bind({
+        let selection = selection.clone();
+        move |_, list_item| {
+            let list_item = list_item
+                .downcast_ref::<ListItem>()
+                .expect("ListItem expected");
+            bind_row(list_item, &selection, ui_cell.get());
+        }
     });
 
     let grid_view = GridView::new(Some(selection.clone()), Some(factory));
-    grid_view.set_max_columns(1);
-    grid_view.set_min_columns(1);
+    let columns = columns_for(config);
+    grid_view.set_max_columns(columns);
+    grid_view.set_min_columns(columns);
+    if layout == Layout::Grid {
+        grid_view.add_css_class("grid-layout");
+    }
     grid_view.set_single_click_activate(true);
     grid_view.set_can_target(false);
 
-    setup_selection_scroll(&grid_view);
-    setup_activation(&grid_view);
+    setup_selection_scroll(&grid_view, details);
 
     grid_view
 }
 
-fn setup_selection_scroll(grid_view: &GridView) {
+fn columns_for(config: &Config) -> u32 {
+    match config.layout {
+        Layout::List => 1,
+        Layout::Grid => config.grid.columns.max(1),
+    }
+}
+
+fn bind_row(list_item: &ListItem, selection: &SingleSelection, ui: Option<&UiController>) {
+    let entry_obj = list_item
+        .item()
+        .and_then(|item| item.downcast::<AppEntryObject>().ok())
+        .expect("AppEntryObject expected");
+
+    let row = list_item
+        .child()
+        .and_downcast::<Box>()
+        .expect("Box expected");
+
+    let position = list_item.position();
+
+    // Group headers would break the tile grid, so only the list shows them.
+    let section = ui
+        .filter(|ui| ui.config.layout == Layout::List)
+        .and_then(|ui| section_for(ui, selection, position, &entry_obj));
+    let badge = ui
+        .filter(|ui| ui.quick_launch().label().is_some() && position < QUICK_LAUNCH_SLOTS)
+        .map(|_| (position + 1).to_string());
+
+    let marked = ui.is_some_and(|ui| ui.is_marked(&entry_obj.id_ref()));
+    let entry = entry_obj.entry_ref();
+    let subtitle = ui.and_then(|ui| ui.config.subtitle.text(&entry));
+
+    populate_app_row(
+        &row,
+        &entry_obj,
+        &RowState {
+            section: section.as_deref(),
+            badge: badge.as_deref(),
+            marked,
+            subtitle,
+        },
+    );
+}
+
+/// Group name to show above the row at `position`, set only on the first
+/// pin of a named group.
+fn section_for(
+    ui: &UiController,
+    selection: &SingleSelection,
+    position: u32,
+    entry_obj: &AppEntryObject,
+) -> Option<String> {
+    let section = ui.pin_section(&entry_obj.id_ref())?;
+
+    let previous = position
+        .checked_sub(1)
+        .and_then(|prev| selection.item(prev))
+        .and_downcast::<AppEntryObject>()
+        .and_then(|prev| ui.pin_section(&prev.id_ref()));
+
+    (previous.as_deref() != Some(section.as_str())).then_some(section)
+}
+
+fn setup_row_menu(row: &Box, list_item: &ListItem, ui_cell: Rc<OnceCell<UiController>>) {
+    let list_item = list_item.downgrade();
+    let click = GestureClick::new();
+    click.set_button(gdk::BUTTON_SECONDARY);
+    click.connect_pressed(move |gesture, _, x, y| {
+        let (Some(ui), Some(list_item)) = (ui_cell.get(), list_item.upgrade()) else {
+            return;
+        };
+        let Some(row) = gesture.widget() else {
+            return;
+        };
+
+        gesture.set_state(gtk4::EventSequenceState::Claimed);
+        ui.select_position(list_item.position());
+        if let Some(entry) = ui.selected_entry() {
+            context_menu::show(ui, entry, &row, Some((x, y)));
+        }
+    });
+    row.add_controller(click);
+}
+
+fn setup_pin_drag(row: &Box, list_item: &ListItem, ui_cell: Rc<OnceCell<UiController>>) {
+    let bound_id = {
+        let list_item = list_item.downgrade();
+        move || {
+            list_item
+                .upgrade()
+                .and_then(|item| item.item())
+                .and_downcast::<AppEntryObject>()
+                .map(|obj| obj.id_ref().to_string())
+        }
+    };
+
+    let drag_source = DragSource::new();
+    drag_source.set_actions(gdk::DragAction::MOVE);
+    drag_source.connect_prepare({
+        let ui_cell = ui_cell.clone();
+        let bound_id = bound_id.clone();
+        move |_, _, _| {
+            let ui = ui_cell.get()?;
+            let id = bound_id().filter(|id| ui.is_pinned(id))?;
+            Some(gdk::ContentProvider::for_value(&id.to_value()))
+        }
+    });
+    row.add_controller(drag_source);
+
+    let drop_target = DropTarget::new(glib::Type::STRING, gdk::DragAction::MOVE);
+    drop_target.connect_drop(move |_, value, _, _| {
+        let (Some(ui), Some(target)) = (ui_cell.get(), bound_id()) else {
+            return false;
+        };
+        let Ok(dragged) = value.get::<String>() else {
+            return false;
+        };
+        ui.move_pin_onto(&dragged, &target)
+    });
+    row.add_controller(drop_target);
+}
+
+fn setup_selection_scroll(grid_view: &GridView, details: Option<DetailsPanel>) {
     let grid_view_clone = grid_view.clone();
 
     if let Some(model) = grid_view.model() {
@@ -444,6 +1114,20 @@ fn setup_selection_scroll(grid_view: &GridView) {
                     grid_view_clone.scroll_to(selected, ListScrollFlags::NONE, None);
                 }
             });
+
+            // `selected-item` also changes without a selection change when
+            // filtering replaces the first row, so it alone drives the panel.
+            if let Some(details) = details {
+                let shown: RefCell<Option<AppEntryObject>> = RefCell::new(None);
+                selection.connect_selected_item_notify(move |selection| {
+                    let obj = selection.selected_item().and_downcast::<AppEntryObject>();
+                    if *shown.borrow() == obj {
+                        return;
+                    }
+                    details.show(obj.as_ref().map(|obj| obj.entry_ref()).as_deref());
+                    shown.replace(obj);
+                });
+            }
         }
     }
 }
@@ -455,15 +1139,39 @@ fn setup_search(search_entry: &Entry, ui: UiController) {
     search_entry.grab_focus();
 }
 
-fn setup_activation(grid_view: &GridView) {
+fn setup_activation(grid_view: &GridView, ui: UiController) {
     let grid_view_clone = grid_view.clone();
-    grid_view.connect_activate(move |_, _| launch_selected(&grid_view_clone));
+    grid_view.connect_activate(move |_, _| launch_selected(&grid_view_clone, &ui));
+}
+
+fn setup_config_reload(container: &Box, ui: UiController) {
+    let file = gio::File::for_path(Config::path());
+    let Ok(monitor) = file.monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE) else {
+        return;
+    };
+
+    monitor.connect_changed(move |_, _, _, event| {
+        if matches!(
+            event,
+            gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created
+        ) {
+            if let Some(config) = Config::try_load() {
+                ui.set_custom_entries(config.custom_entries());
+            }
+        }
+    });
+
+    container.connect_destroy(move |_| {
+        monitor.cancel();
+    });
 }
 
 fn start_loader(ui: UiController) {
     let done_received = Rc::new(Cell::new(false));
     let finalized = Rc::new(Cell::new(false));
 
+    ui.set_custom_entries(ui.config.custom_entries());
+
     let (tx, rx) = async_channel::unbounded::<LoaderMsg>();
     let pinned_snapshot = ui.pinned_snapshot();
     crate::desktop::spawn_load_entries(tx, pinned_snapshot);
@@ -504,6 +1212,7 @@ fn start_loader(ui: UiController) {
                             drop(q);
                             ui2.cleanup_stale_pins();
                         }
+                        ui2.refresh_categories();
 
                         return glib::ControlFlow::Break;
                     }
@@ -523,7 +1232,7 @@ fn start_loader(ui: UiController) {
                     pending.borrow_mut().extend(apps);
                     schedule_drain();
                 }
-                LoaderMsg::App(app) => ui.upsert_entry(app),
+                LoaderMsg::App(app) => ui.upsert_entry(*app),
                 LoaderMsg::Remove(ids) => ui.remove_ids(ids),
                 LoaderMsg::Done => {
                     done_received.set(true);
@@ -540,15 +1249,37 @@ fn start_loader(ui: UiController) {
     glib::timeout_add_local(Duration::from_millis(1), || glib::ControlFlow::Break);
 }
 
-fn compute_score(name: &str, query: &str) -> i64 {
+fn running_window_classes() -> Vec<String> {
+    crate::hyprland::clients()
+        .into_iter()
+        .flat_map(|client| [client.class, client.initial_class])
+        .filter(|class| !class.is_empty())
+        .collect()
+}
+
+fn compute_score(name: &str, keywords: &[String], query: &str) -> i64 {
     if query.is_empty() {
         return 0;
     }
 
-    SkimMatcherV2::default()
-        .ignore_case()
-        .fuzzy_match(name, query)
-        .unwrap_or(SCORE_NO_MATCH)
+    match_score(
+        &SkimMatcherV2::default().ignore_case(),
+        name,
+        keywords,
+        query,
+    )
+}
+
+/// Name matches rank above keyword matches of the same quality.
+fn match_score(matcher: &SkimMatcherV2, name: &str, keywords: &[String], query: &str) -> i64 {
+    let name_score = matcher.fuzzy_match(name, query);
+    let keyword_score = keywords
+        .iter()
+        .filter_map(|keyword| matcher.fuzzy_match(keyword, query))
+        .max()
+        .map(|score| score / KEYWORD_SCORE_DIVISOR);
+
+    name_score.max(keyword_score).unwrap_or(SCORE_NO_MATCH)
 }
 
 fn find_in_base(base: &ListStore, id: &str) -> Option<u32> {
@@ -562,13 +1293,17 @@ fn find_in_base(base: &ListStore, id: &str) -> Option<u32> {
     None
 }
 
-fn launch_selected(grid_view: &GridView) {
+fn launch_selected(grid_view: &GridView, ui: &UiController) {
     if let Some(model) = grid_view.model() {
         if let Some(selection) = model.downcast_ref::<SingleSelection>() {
             if selection.selected() != u32::MAX {
                 if let Some(item) = selection.selected_item() {
                     if let Ok(entry_obj) = item.downcast::<AppEntryObject>() {
-                        crate::launcher::launch_app(&entry_obj.entry());
+                        if !crate::launcher::launch_app(&entry_obj.entry(), false, Some(ui))
+                            || !ui.config.close_on_launch
+                        {
+                            return;
+                        }
                         if let Some(window) = grid_view
                             .root()
                             .and_then(|root| root.downcast::<gtk4::Window>().ok())
@@ -594,6 +1329,7 @@ mod imp {
         pub name: RefCell<String>,
         pub name_key: RefCell<String>,
         pub score: Cell<i64>,
+        pub running: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -630,6 +1366,12 @@ impl AppEntryObject {
             .clone()
     }
 
+    pub fn entry_ref(&self) -> std::cell::Ref<'_, crate::desktop::DesktopEntry> {
+        std::cell::Ref::map(self.imp().entry.borrow(), |entry| {
+            entry.as_ref().expect("Entry should be set")
+        })
+    }
+
     pub fn score(&self) -> i64 {
         self.imp().score.get()
     }
@@ -638,6 +1380,14 @@ impl AppEntryObject {
         self.imp().score.set(score);
     }
 
+    pub fn is_running(&self) -> bool {
+        self.imp().running.get()
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.imp().running.set(running);
+    }
+
     pub fn id_ref(&self) -> std::cell::Ref<'_, str> {
         std::cell::Ref::map(self.imp().id.borrow(), |s| s.as_str())
     }
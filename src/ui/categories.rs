@@ -0,0 +1,95 @@
+use gtk4::{
+    glib::object::CastNone,
+    prelude::{BoxExt, ToggleButtonExt, WidgetExt},
+    Box, Orientation, ToggleButton,
+};
+
+use super::UiController;
+use crate::desktop::MainCategory;
+
+pub fn create_category_bar() -> Box {
+    let bar = Box::new(Orientation::Horizontal, 4);
+    bar.set_margin_bottom(8);
+    bar.set_margin_start(16);
+    bar.set_margin_end(16);
+    bar.add_css_class("category-bar");
+
+    let all = create_tab("All");
+    all.set_active(true);
+    bar.append(&all);
+
+    for category in MainCategory::ALL {
+        let tab = create_tab(category.label());
+        tab.set_group(Some(&all));
+        tab.set_visible(false);
+        bar.append(&tab);
+    }
+
+    bar
+}
+
+fn create_tab(label: &str) -> ToggleButton {
+    let tab = ToggleButton::with_label(label);
+    tab.add_css_class("category-tab");
+    // Keyboard focus stays in the search entry, tabs switch with Ctrl+Tab.
+    tab.set_can_focus(false);
+    tab.set_focus_on_click(false);
+    tab
+}
+
+pub fn setup_category_bar(bar: &Box, ui: UiController) {
+    for (tab, category) in tabs(bar) {
+        let ui = ui.clone();
+        tab.connect_toggled(move |tab| {
+            if tab.is_active() {
+                ui.set_category(category);
+            }
+        });
+    }
+}
+
+fn tabs(bar: &Box) -> Vec<(ToggleButton, Option<MainCategory>)> {
+    let mut tabs = Vec::new();
+    let mut child = bar.first_child().and_downcast::<ToggleButton>();
+    let mut categories = std::iter::once(None).chain(MainCategory::ALL.map(Some));
+
+    while let (Some(tab), Some(category)) = (child, categories.next()) {
+        child = tab.next_sibling().and_downcast::<ToggleButton>();
+        tabs.push((tab, category));
+    }
+    tabs
+}
+
+/// Shows the tabs of categories in `present`. Returns false when the active
+/// tab got hidden.
+pub fn update_tabs(bar: &Box, present: &[MainCategory]) -> bool {
+    let mut active_visible = true;
+    for (tab, category) in tabs(bar) {
+        let visible = category.is_none_or(|category| present.contains(&category));
+        tab.set_visible(visible);
+        if tab.is_active() && !visible {
+            active_visible = false;
+        }
+    }
+    active_visible
+}
+
+pub fn activate_tab(bar: &Box, category: Option<MainCategory>) {
+    if let Some((tab, _)) = tabs(bar).into_iter().find(|(_, c)| *c == category) {
+        tab.set_active(true);
+    }
+}
+
+pub fn cycle(bar: &Box, current: Option<MainCategory>, delta: i32) -> Option<MainCategory> {
+    let visible: Vec<Option<MainCategory>> = tabs(bar)
+        .into_iter()
+        .filter(|(tab, _)| tab.is_visible())
+        .map(|(_, category)| category)
+        .collect();
+    if visible.is_empty() {
+        return current;
+    }
+
+    let index = visible.iter().position(|c| *c == current).unwrap_or(0) as i32;
+    visible[(index + delta).rem_euclid(visible.len() as i32) as usize]
+}
@@ -1,12 +1,64 @@
 use gtk4::{
-    glib::object::Cast,
+    glib::object::{Cast, CastNone},
+    pango::EllipsizeMode,
     prelude::{BoxExt, WidgetExt},
     Box, Image, Label, Orientation,
 };
+use serde::Deserialize;
 
-use super::AppEntryObject;
+use super::{icons, AppEntryObject};
+use crate::{config::GridConfig, desktop::DesktopEntry};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    List,
+    Grid,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subtitle {
+    #[default]
+    Auto,
+    GenericName,
+    Comment,
+    None,
+}
+
+impl Subtitle {
+    pub fn text(self, entry: &DesktopEntry) -> Option<&str> {
+        let text = match self {
+            Subtitle::Auto => entry.generic_name.as_ref().or(entry.comment.as_ref()),
+            Subtitle::GenericName => entry.generic_name.as_ref(),
+            Subtitle::Comment => entry.comment.as_ref(),
+            Subtitle::None => None,
+        };
+        // A generic name equal to the name adds nothing.
+        text.map(String::as_str).filter(|text| *text != entry.name)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RowState<'a> {
+    pub section: Option<&'a str>,
+    pub badge: Option<&'a str>,
+    pub marked: bool,
+    pub subtitle: Option<&'a str>,
+}
 
 pub fn create_app_row() -> Box {
+    let item = Box::new(Orientation::Vertical, 0);
+
+    // Pin group header, only shown on the first row of a group
+    let section_label = Label::new(None);
+    section_label.set_halign(gtk4::Align::Start);
+    section_label.set_widget_name("section-header");
+    section_label.add_css_class("section-header");
+    section_label.set_visible(false);
+    item.append(&section_label);
+
     let row = Box::new(Orientation::Horizontal, 12);
     row.add_css_class("app-row");
 
@@ -26,25 +78,125 @@ pub fn create_app_row() -> Box {
     name_label.add_css_class("app-name");
     text_box.append(&name_label);
 
+    text_box.append(&create_subtitle_label());
+
     row.append(&text_box);
 
-    row
+    let running_label = Label::new(Some("●"));
+    running_label.set_widget_name("app-running");
+    running_label.add_css_class("app-running");
+    running_label.set_tooltip_text(Some("Running"));
+    running_label.set_visible(false);
+    row.append(&running_label);
+
+    // Quick-launch index, only set on the first nine rows
+    let badge_label = Label::new(None);
+    badge_label.set_widget_name("quick-launch-badge");
+    badge_label.add_css_class("quick-launch-badge");
+    badge_label.set_valign(gtk4::Align::Center);
+    badge_label.set_visible(false);
+    row.append(&badge_label);
+
+    item.append(&row);
+    item
+}
+
+/// Grid variant of [`create_app_row`]. The children are in the same order so
+/// [`populate_app_row`] fills both.
+pub fn create_app_tile(grid: &GridConfig) -> Box {
+    let item = Box::new(Orientation::Vertical, 0);
+
+    // Pin group headers are not shown in the grid, the label only keeps the layout
+    let section_label = Label::new(None);
+    section_label.set_visible(false);
+    item.append(&section_label);
+
+    let tile = Box::new(Orientation::Vertical, 6);
+    tile.add_css_class("app-tile");
+    tile.set_size_request(grid.tile_size, grid.tile_size);
+
+    let icon = Image::new();
+    icon.set_pixel_size(grid.icon_size);
+    icon.set_widget_name("app-icon");
+    icon.set_halign(gtk4::Align::Center);
+    tile.append(&icon);
+
+    let text_box = Box::new(Orientation::Vertical, 2);
+
+    let name_label = Label::new(None);
+    name_label.set_widget_name("app-name");
+    name_label.add_css_class("app-name");
+    name_label.set_justify(gtk4::Justification::Center);
+    name_label.set_ellipsize(EllipsizeMode::End);
+    name_label.set_max_width_chars(1);
+    name_label.set_hexpand(true);
+    text_box.append(&name_label);
+
+    let subtitle_label = create_subtitle_label();
+    subtitle_label.set_halign(gtk4::Align::Center);
+    text_box.append(&subtitle_label);
+
+    tile.append(&text_box);
+
+    let running_label = Label::new(Some("●"));
+    running_label.set_widget_name("app-running");
+    running_label.add_css_class("app-running");
+    running_label.set_tooltip_text(Some("Running"));
+    running_label.set_visible(false);
+    tile.append(&running_label);
+
+    let badge_label = Label::new(None);
+    badge_label.set_widget_name("quick-launch-badge");
+    badge_label.add_css_class("quick-launch-badge");
+    badge_label.set_halign(gtk4::Align::Center);
+    badge_label.set_visible(false);
+    tile.append(&badge_label);
+
+    item.append(&tile);
+    item
+}
+
+fn create_subtitle_label() -> Label {
+    let subtitle_label = Label::new(None);
+    subtitle_label.set_halign(gtk4::Align::Start);
+    subtitle_label.set_widget_name("app-subtitle");
+    subtitle_label.add_css_class("app-subtitle");
+    subtitle_label.set_ellipsize(EllipsizeMode::End);
+    subtitle_label.set_max_width_chars(1);
+    subtitle_label.set_hexpand(true);
+    subtitle_label.set_visible(false);
+    subtitle_label
 }
 
-pub fn populate_app_row(row: &Box, entry_obj: &AppEntryObject) {
-    let entry = entry_obj.entry();
+pub fn populate_app_row(item: &Box, entry_obj: &AppEntryObject, state: &RowState) {
+    let entry = entry_obj.entry_ref();
+    let RowState {
+        section,
+        badge,
+        marked,
+        subtitle,
+    } = *state;
+
+    let Some(section_widget) = item.first_child() else {
+        return;
+    };
+    if let Some(section_label) = section_widget.downcast_ref::<Label>() {
+        section_label.set_text(section.unwrap_or_default());
+        section_label.set_visible(section.is_some());
+    }
+
+    let Some(row) = section_widget.next_sibling().and_downcast::<Box>() else {
+        return;
+    };
+    if marked {
+        row.add_css_class("marked");
+    } else {
+        row.remove_css_class("marked");
+    }
 
     if let Some(icon_widget) = row.first_child() {
         if let Some(icon) = icon_widget.downcast_ref::<Image>() {
-            if let Some(icon_name) = &entry.icon {
-                if icon_name.starts_with('/') {
-                    icon.set_from_file(Some(icon_name));
-                } else {
-                    icon.set_icon_name(Some(icon_name));
-                }
-            } else {
-                icon.set_icon_name(Some("application-x-executable"));
-            }
+            icons::apply(icon, entry.icon.as_deref());
         }
     }
 
@@ -55,8 +207,29 @@ pub fn populate_app_row(row: &Box, entry_obj: &AppEntryObject) {
                     if let Some(label) = name_label.downcast_ref::<Label>() {
                         label.set_text(&entry.name);
                     }
+                    if let Some(subtitle_label) = name_label.next_sibling().and_downcast::<Label>()
+                    {
+                        // Skip the relayout when a recycled row shows the same text.
+                        let text = subtitle.unwrap_or_default();
+                        if subtitle_label.text() != text {
+                            subtitle_label.set_text(text);
+                        }
+                        subtitle_label.set_visible(subtitle.is_some());
+                    }
                 }
             }
         }
     }
+
+    let Some(badge_widget) = row.last_child() else {
+        return;
+    };
+    if let Some(badge_label) = badge_widget.downcast_ref::<Label>() {
+        badge_label.set_text(badge.unwrap_or_default());
+        badge_label.set_visible(badge.is_some());
+    }
+
+    if let Some(running_label) = badge_widget.prev_sibling() {
+        running_label.set_visible(entry_obj.is_running());
+    }
 }
@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use gtk4::{
+    gdk::{self, prelude::DisplayExt},
+    gio::{self, prelude::*},
+    glib,
+    prelude::*,
+    PopoverMenu, Widget,
+};
+
+use super::UiController;
+use crate::{
+    config::Config,
+    desktop::DesktopEntry,
+    launcher::{self, LaunchOptions},
+};
+
+const ACTION_PREFIX: &str = "row";
+
+type RowHandler = Box<dyn Fn(&UiController, &DesktopEntry)>;
+
+pub fn show(ui: &UiController, entry: DesktopEntry, parent: &Widget, point: Option<(f64, f64)>) {
+    let popover = PopoverMenu::from_model(Some(&build_menu(ui, &entry)));
+    popover.set_has_arrow(false);
+    popover.set_halign(gtk4::Align::Start);
+    popover.insert_action_group(ACTION_PREFIX, Some(&build_actions(ui, entry)));
+    popover.set_parent(parent);
+
+    if let Some((x, y)) = point {
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+    }
+
+    // Menu actions run after `closed`, so the popover is only dropped afterwards.
+    popover.connect_closed(|popover| {
+        let popover = popover.clone();
+        glib::idle_add_local_once(move || popover.unparent());
+    });
+
+    popover.popup();
+}
+
+fn build_menu(ui: &UiController, entry: &DesktopEntry) -> gio::Menu {
+    let menu = gio::Menu::new();
+
+    let launch = gio::Menu::new();
+    launch.append(Some("Launch"), Some("row.launch"));
+    launch.append(Some("Launch in terminal"), Some("row.terminal"));
+    menu.append_section(None, &launch);
+
+    if !entry.actions.is_empty() {
+        let actions = gio::Menu::new();
+        for action in &entry.actions {
+            let item = gio::MenuItem::new(Some(&action.name), None);
+            item.set_action_and_target_value(Some("row.action"), Some(&action.id.to_variant()));
+            actions.append_item(&item);
+        }
+        menu.append_section(None, &actions);
+    }
+
+    let manage = gio::Menu::new();
+    manage.append(
+        Some(if ui.is_pinned(&entry.id) {
+            "Unpin"
+        } else {
+            "Pin"
+        }),
+        Some("row.pin"),
+    );
+    manage.append(
+        Some(if ui.is_hidden(&entry.id) {
+            "Unhide"
+        } else {
+            "Hide"
+        }),
+        Some("row.hide"),
+    );
+    menu.append_section(None, &manage);
+
+    let source = gio::Menu::new();
+    source.append(Some("Copy Exec line"), Some("row.copy-exec"));
+    if let Some(path) = &entry.source_path {
+        let label = if *path == Config::path() {
+            "Open config.toml in editor"
+        } else {
+            "Open .desktop file in editor"
+        };
+        source.append(Some(label), Some("row.open-source"));
+    }
+    menu.append_section(None, &source);
+
+    menu
+}
+
+fn build_actions(ui: &UiController, entry: DesktopEntry) -> gio::SimpleActionGroup {
+    let group = gio::SimpleActionGroup::new();
+
+    let add = |name: &str, handler: RowHandler| {
+        let action = gio::SimpleAction::new(name, None);
+        let ui = ui.clone();
+        let entry = entry.clone();
+        action.connect_activate(move |_, _| handler(&ui, &entry));
+        group.add_action(&action);
+    };
+
+    add(
+        "launch",
+        Box::new(|ui, entry| launch(ui, entry, LaunchOptions::default())),
+    );
+    add(
+        "terminal",
+        Box::new(|ui, entry| {
+            let options = LaunchOptions {
+                in_terminal: true,
+                ..LaunchOptions::default()
+            };
+            launch(ui, entry, options)
+        }),
+    );
+    add(
+        "pin",
+        Box::new(|ui, entry| {
+            let now_pinned = ui.toggle_pin(&entry.id);
+            println!(
+                "{}: {}",
+                if now_pinned { "Pinned" } else { "Unpinned" },
+                entry.name
+            );
+        }),
+    );
+    add(
+        "hide",
+        Box::new(|ui, entry| {
+            let now_hidden = ui.toggle_hidden(&entry.id);
+            println!(
+                "{}: {}",
+                if now_hidden { "Hidden" } else { "Unhidden" },
+                entry.name
+            );
+        }),
+    );
+    add(
+        "copy-exec",
+        Box::new(|_, entry| {
+            if let Some(display) = gdk::Display::default() {
+                display.clipboard().set_text(&entry.exec);
+            }
+        }),
+    );
+    add(
+        "open-source",
+        Box::new(|ui, entry| {
+            let Some(path) = &entry.source_path else {
+                return;
+            };
+            match open_in_editor(path) {
+                Ok(()) if ui.config().close_on_launch => ui.close_window(),
+                Ok(()) => {}
+                Err(e) => {
+                    eprintln!("Failed to open {}: {}", path.display(), e);
+                    ui.show_error(&format!("Failed to open {}", path.display()), &e);
+                }
+            }
+        }),
+    );
+
+    let action = gio::SimpleAction::new("action", Some(glib::VariantTy::STRING));
+    {
+        let ui = ui.clone();
+        action.connect_activate(move |_, parameter| {
+            let Some(id) = parameter.and_then(|parameter| parameter.str()) else {
+                return;
+            };
+            let options = LaunchOptions {
+                action: Some(id.to_string()),
+                ..LaunchOptions::default()
+            };
+            launch(&ui, &entry, options);
+        });
+    }
+    group.add_action(&action);
+
+    group
+}
+
+fn launch(ui: &UiController, entry: &DesktopEntry, options: LaunchOptions) {
+    if launcher::launch_app_with(entry, &options, Some(ui)) && ui.config().close_on_launch {
+        ui.close_window();
+    }
+}
+
+/// Opens `path` with the default handler for plain text, as the one for
+/// .desktop files would start the app instead.
+fn open_in_editor(path: &Path) -> Result<(), String> {
+    let app = gio::AppInfo::default_for_type("text/plain", false)
+        .ok_or_else(|| "No default text editor is set".to_string())?;
+    let context = gdk::Display::default().map(|display| display.app_launch_context());
+
+    app.launch(&[gio::File::for_path(path)], context.as_ref())
+        .map_err(|e| e.to_string())
+}
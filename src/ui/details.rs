@@ -0,0 +1,152 @@
+use gtk4::{
+    glib,
+    pango::WrapMode,
+    prelude::{BoxExt, WidgetExt},
+    Box, Image, Label, Orientation,
+};
+
+use super::icons;
+use crate::{desktop::DesktopEntry, history};
+
+const ICON_SIZE: i32 = 96;
+const PANEL_WIDTH: i32 = 260;
+
+#[derive(Clone)]
+pub struct DetailsPanel {
+    root: Box,
+    icon: Image,
+    name: Label,
+    comment: Label,
+    exec: Label,
+    source: Label,
+    categories: Label,
+    actions: Label,
+    last_launched: Label,
+}
+
+impl DetailsPanel {
+    pub fn new() -> Self {
+        history::preload();
+
+        let root = Box::new(Orientation::Vertical, 6);
+        root.add_css_class("details-panel");
+        root.set_size_request(PANEL_WIDTH, -1);
+        root.set_margin_end(16);
+
+        let icon = Image::new();
+        icon.set_pixel_size(ICON_SIZE);
+        icon.add_css_class("details-icon");
+        root.append(&icon);
+
+        let name = value_label("details-name");
+        name.set_halign(gtk4::Align::Center);
+        name.set_justify(gtk4::Justification::Center);
+        root.append(&name);
+
+        let comment = value_label("details-comment");
+        comment.set_halign(gtk4::Align::Center);
+        comment.set_justify(gtk4::Justification::Center);
+        root.append(&comment);
+
+        let exec = field(&root, "Exec");
+        let source = field(&root, "File");
+        let categories = field(&root, "Categories");
+        let actions = field(&root, "Actions");
+        let last_launched = field(&root, "Last launched");
+
+        Self {
+            root,
+            icon,
+            name,
+            comment,
+            exec,
+            source,
+            categories,
+            actions,
+            last_launched,
+        }
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.root
+    }
+
+    pub fn show(&self, entry: Option<&DesktopEntry>) {
+        let Some(entry) = entry else {
+            self.root.set_opacity(0.0);
+            return;
+        };
+        self.root.set_opacity(1.0);
+
+        icons::apply(&self.icon, entry.icon.as_deref());
+
+        self.name.set_text(&entry.name);
+        set_optional(&self.comment, entry.comment.as_deref());
+        set_optional(&self.exec, Some(&entry.exec));
+
+        let source = entry
+            .source_path
+            .as_ref()
+            .map(|path| path.display().to_string());
+        set_optional(&self.source, source.as_deref());
+
+        let categories = entry
+            .main_categories()
+            .map(|category| category.label())
+            .collect::<Vec<_>>()
+            .join(", ");
+        set_optional(&self.categories, Some(&categories));
+
+        let actions = entry
+            .actions
+            .iter()
+            .map(|action| action.name.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        set_optional(&self.actions, Some(&actions));
+
+        let last_launched = history::last_launched(&entry.id)
+            .and_then(|secs| glib::DateTime::from_unix_local(secs).ok())
+            .and_then(|time| time.format("%Y-%m-%d %H:%M").ok());
+        set_optional(
+            &self.last_launched,
+            Some(last_launched.as_deref().unwrap_or("Never")),
+        );
+    }
+}
+
+fn value_label(css_class: &str) -> Label {
+    let label = Label::new(None);
+    label.set_halign(gtk4::Align::Start);
+    label.set_xalign(0.0);
+    label.set_wrap(true);
+    label.set_wrap_mode(WrapMode::WordChar);
+    label.set_max_width_chars(1);
+    label.add_css_class(css_class);
+    label
+}
+
+/// Adds a heading and a value label to `root`, returning the value label.
+/// The heading is the value's previous sibling so both hide together.
+fn field(root: &Box, heading: &str) -> Label {
+    let heading = Label::new(Some(heading));
+    heading.set_halign(gtk4::Align::Start);
+    heading.add_css_class("details-heading");
+    root.append(&heading);
+
+    let value = value_label("details-value");
+    root.append(&value);
+    value
+}
+
+fn set_optional(label: &Label, text: Option<&str>) {
+    let text = text.filter(|text| !text.is_empty());
+    label.set_text(text.unwrap_or_default());
+    label.set_visible(text.is_some());
+
+    if label.has_css_class("details-value") {
+        if let Some(heading) = label.prev_sibling() {
+            heading.set_visible(text.is_some());
+        }
+    }
+}
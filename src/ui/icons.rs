@@ -0,0 +1,389 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use gtk4::{gdk, gdk_pixbuf::Pixbuf, gio, glib, prelude::*, IconTheme, Image};
+
+pub const FALLBACK_ICON: &str = "application-x-executable";
+
+const DEFAULT_PIXEL_SIZE: i32 = 32;
+
+const PIXMAP_EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+const REQUEST_DATA: &str = "hbucket-icon-request";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedIcon {
+    Name(String),
+    File(PathBuf),
+}
+
+type TextureKey = (PathBuf, i32);
+
+struct PendingDecode {
+    cancellable: gio::Cancellable,
+    waiters: Vec<glib::WeakRef<Image>>,
+}
+
+/// Pixel data handed back from the decoding thread, as textures can only be
+/// created on the main thread.
+struct DecodedIcon {
+    width: i32,
+    height: i32,
+    has_alpha: bool,
+    rowstride: usize,
+    pixels: glib::Bytes,
+}
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, ResolvedIcon>> = RefCell::new(HashMap::new());
+    /// Decoded file icons, `None` for files that failed to decode.
+    static TEXTURES: RefCell<HashMap<TextureKey, Option<gdk::Texture>>> =
+        RefCell::new(HashMap::new());
+    static PENDING: RefCell<HashMap<TextureKey, PendingDecode>> = RefCell::new(HashMap::new());
+}
+
+/// Sets `image` to the icon described by an `Icon=` value. Image files are
+/// decoded off the main thread, showing the fallback icon until they are
+/// ready; rebinding the image in the meantime drops the stale request.
+pub fn apply(image: &Image, icon: Option<&str>) {
+    match resolve(icon) {
+        ResolvedIcon::Name(name) => {
+            cancel_request(image);
+            image.set_icon_name(Some(&name));
+        }
+        ResolvedIcon::File(path) => load_file(image, path),
+    }
+}
+
+pub fn resolve(icon: Option<&str>) -> ResolvedIcon {
+    let Some(icon) = icon.map(str::trim).filter(|icon| !icon.is_empty()) else {
+        return ResolvedIcon::Name(FALLBACK_ICON.to_string());
+    };
+
+    if let Some(cached) = CACHE.with_borrow(|cache| cache.get(icon).cloned()) {
+        return cached;
+    }
+
+    let resolved = match icon_theme() {
+        Some(theme) => lookup(&theme, icon),
+        None => ResolvedIcon::Name(icon.to_string()),
+    };
+    CACHE.with_borrow_mut(|cache| cache.insert(icon.to_string(), resolved.clone()));
+    resolved
+}
+
+fn lookup(theme: &IconTheme, icon: &str) -> ResolvedIcon {
+    let path = Path::new(icon);
+
+    if path.is_absolute() {
+        if path.is_file() {
+            return ResolvedIcon::File(path.to_path_buf());
+        }
+    } else if icon.contains('/') {
+        // Relative paths are taken relative to the data and pixmap directories.
+        if let Some(found) = search_dirs()
+            .iter()
+            .map(|dir| dir.join(path))
+            .find(|candidate| candidate.is_file())
+        {
+            return ResolvedIcon::File(found);
+        }
+    }
+
+    // `foo.png` or a missing `/opt/foo/foo.svg` may still be a theme icon.
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(icon);
+    let has_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PIXMAP_EXTENSIONS.contains(&ext));
+    let name = if has_extension {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(icon)
+    } else {
+        file_name
+    };
+
+    if theme.has_icon(name) {
+        return ResolvedIcon::Name(name.to_string());
+    }
+
+    if let Some(found) = find_pixmap(file_name, name, has_extension) {
+        return ResolvedIcon::File(found);
+    }
+
+    ResolvedIcon::Name(FALLBACK_ICON.to_string())
+}
+
+fn find_pixmap(file_name: &str, name: &str, has_extension: bool) -> Option<PathBuf> {
+    let candidates: Vec<String> = if has_extension {
+        vec![file_name.to_string()]
+    } else {
+        PIXMAP_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{}.{}", name, ext))
+            .collect()
+    };
+
+    pixmap_dirs()
+        .iter()
+        .flat_map(|dir| candidates.iter().map(move |file| dir.join(file)))
+        .find(|candidate| candidate.is_file())
+}
+
+fn load_file(image: &Image, path: PathBuf) {
+    let pixel_size = match image.pixel_size() {
+        size if size > 0 => size,
+        _ => DEFAULT_PIXEL_SIZE,
+    };
+    let key = (path, pixel_size * image.scale_factor());
+
+    if request(image).as_ref() == Some(&key) {
+        return;
+    }
+    cancel_request(image);
+
+    if let Some(texture) = TEXTURES.with_borrow(|textures| textures.get(&key).cloned()) {
+        set_texture(image, texture.as_ref());
+        return;
+    }
+
+    image.set_icon_name(Some(FALLBACK_ICON));
+    set_request(image, key.clone());
+
+    let already_pending = PENDING.with_borrow_mut(|pending| match pending.get_mut(&key) {
+        Some(decode) => {
+            decode.waiters.push(image.downgrade());
+            true
+        }
+        None => {
+            pending.insert(
+                key.clone(),
+                PendingDecode {
+                    cancellable: gio::Cancellable::new(),
+                    waiters: vec![image.downgrade()],
+                },
+            );
+            false
+        }
+    });
+    if already_pending {
+        return;
+    }
+
+    let cancellable = PENDING.with_borrow(|pending| pending[&key].cancellable.clone());
+    glib::spawn_future_local(async move {
+        let result = {
+            let (path, size) = key.clone();
+            let cancellable = cancellable.clone();
+            gio::spawn_blocking(move || decode(&path, size, &cancellable)).await
+        };
+        let result = result.unwrap_or_else(|_| {
+            Err(glib::Error::new(
+                gio::IOErrorEnum::Failed,
+                "Icon decoder panicked",
+            ))
+        });
+        finish_decode(key, &cancellable, result);
+    });
+}
+
+fn decode(
+    path: &Path,
+    size: i32,
+    cancellable: &gio::Cancellable,
+) -> Result<DecodedIcon, glib::Error> {
+    cancellable.set_error_if_cancelled()?;
+
+    let pixbuf = Pixbuf::from_file_at_scale(path, size, size, true)?;
+    Ok(DecodedIcon {
+        width: pixbuf.width(),
+        height: pixbuf.height(),
+        has_alpha: pixbuf.has_alpha(),
+        rowstride: pixbuf.rowstride() as usize,
+        pixels: pixbuf.read_pixel_bytes(),
+    })
+}
+
+fn finish_decode(
+    key: TextureKey,
+    cancellable: &gio::Cancellable,
+    result: Result<DecodedIcon, glib::Error>,
+) {
+    // A cancelled decode may have been replaced by a new one for the same key.
+    let waiters = PENDING.with_borrow_mut(|pending| {
+        match pending.get(&key) {
+            Some(decode) if decode.cancellable == *cancellable => {}
+            _ => return None,
+        }
+        pending.remove(&key).map(|decode| decode.waiters)
+    });
+    let Some(waiters) = waiters else {
+        return;
+    };
+
+    let texture = match result {
+        Ok(decoded) => {
+            let format = if decoded.has_alpha {
+                gdk::MemoryFormat::R8g8b8a8
+            } else {
+                gdk::MemoryFormat::R8g8b8
+            };
+            let texture = gdk::MemoryTexture::new(
+                decoded.width,
+                decoded.height,
+                format,
+                &decoded.pixels,
+                decoded.rowstride,
+            );
+            Some(texture.upcast::<gdk::Texture>())
+        }
+        Err(e) if e.matches(gio::IOErrorEnum::Cancelled) => return,
+        Err(e) => {
+            eprintln!("Failed to load icon {}: {}", key.0.display(), e);
+            None
+        }
+    };
+    TEXTURES.with_borrow_mut(|textures| textures.insert(key.clone(), texture.clone()));
+
+    for image in waiters.iter().filter_map(|waiter| waiter.upgrade()) {
+        if request(&image).as_ref() == Some(&key) {
+            set_texture(&image, texture.as_ref());
+        }
+    }
+}
+
+fn set_texture(image: &Image, texture: Option<&gdk::Texture>) {
+    take_request(image);
+    match texture {
+        Some(texture) => image.set_paintable(Some(texture)),
+        None => image.set_icon_name(Some(FALLBACK_ICON)),
+    }
+}
+
+fn cancel_request(image: &Image) {
+    let Some(key) = take_request(image) else {
+        return;
+    };
+
+    PENDING.with_borrow_mut(|pending| {
+        let Some(decode) = pending.get_mut(&key) else {
+            return;
+        };
+        decode.waiters.retain(|waiter| {
+            waiter
+                .upgrade()
+                .is_some_and(|waiting| request(&waiting).as_ref() == Some(&key))
+        });
+        if decode.waiters.is_empty() {
+            decode.cancellable.cancel();
+            pending.remove(&key);
+        }
+    });
+}
+
+/// The file icon `image` is waiting for. It lives on the image itself so it
+/// is dropped with it instead of being inherited by a later image.
+fn request(image: &Image) -> Option<TextureKey> {
+    // SAFETY: `REQUEST_DATA` is only ever set to a `TextureKey`.
+    unsafe {
+        image
+            .data::<TextureKey>(REQUEST_DATA)
+            .map(|key| key.as_ref().clone())
+    }
+}
+
+fn set_request(image: &Image, key: TextureKey) {
+    unsafe { image.set_data(REQUEST_DATA, key) }
+}
+
+fn take_request(image: &Image) -> Option<TextureKey> {
+    unsafe { image.steal_data::<TextureKey>(REQUEST_DATA) }
+}
+
+/// The display's icon theme, extended once with the Flatpak export
+/// directories in case they are missing from `XDG_DATA_DIRS`.
+fn icon_theme() -> Option<IconTheme> {
+    thread_local! {
+        static THEME: RefCell<Option<IconTheme>> = const { RefCell::new(None) };
+    }
+
+    if let Some(theme) = THEME.with_borrow(Clone::clone) {
+        return Some(theme);
+    }
+
+    let theme = IconTheme::for_display(&gdk::Display::default()?);
+    for dir in flatpak_export_dirs() {
+        let icons = dir.join("icons");
+        if icons.is_dir() && !theme.search_path().contains(&icons) {
+            theme.add_search_path(&icons);
+        }
+    }
+    theme.connect_changed(|_| CACHE.with_borrow_mut(HashMap::clear));
+
+    THEME.with_borrow_mut(|cached| *cached = Some(theme.clone()));
+    Some(theme)
+}
+
+fn flatpak_export_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(home).join(".local/share/flatpak/exports/share"));
+    }
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs
+}
+
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Ok(xdg_data_home) = std::env::var("XDG_DATA_HOME") {
+        dirs.push(PathBuf::from(xdg_data_home));
+    } else if let Ok(home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(home).join(".local/share"));
+    }
+
+    match std::env::var("XDG_DATA_DIRS") {
+        Ok(xdg_data_dirs) => dirs.extend(
+            xdg_data_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        ),
+        Err(_) => {
+            dirs.push(PathBuf::from("/usr/local/share"));
+            dirs.push(PathBuf::from("/usr/share"));
+        }
+    }
+
+    for dir in flatpak_export_dirs() {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+fn pixmap_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("pixmaps"))
+        .collect();
+    let usr_pixmaps = PathBuf::from("/usr/share/pixmaps");
+    if !dirs.contains(&usr_pixmaps) {
+        dirs.push(usr_pixmaps);
+    }
+    dirs
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = data_dirs();
+    dirs.extend(pixmap_dirs());
+    dirs
+}
@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashSet};
+
+/// Pinned desktop ids in the user's order, with optional group names.
+/// Ungrouped pins come first, followed by each group in the order its
+/// first pin appears.
+#[derive(Debug, Default)]
+pub struct Pins {
+    order: Vec<String>,
+    groups: BTreeMap<String, String>,
+}
+
+impl Pins {
+    pub fn new(order: Vec<String>, mut groups: BTreeMap<String, String>) -> Self {
+        let mut seen = HashSet::new();
+        let order = order
+            .into_iter()
+            .filter(|id| seen.insert(id.clone()))
+            .collect();
+        groups.retain(|id, _| seen.contains(id));
+
+        Self { order, groups }
+    }
+
+    pub fn order(&self) -> &[String] {
+        &self.order
+    }
+
+    pub fn groups(&self) -> &BTreeMap<String, String> {
+        &self.groups
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.order.iter().any(|pinned| pinned == id)
+    }
+
+    pub fn group(&self, id: &str) -> Option<&str> {
+        self.groups.get(id).map(String::as_str)
+    }
+
+    pub fn toggle(&mut self, id: &str) -> bool {
+        if self.remove(id) {
+            false
+        } else {
+            self.order.push(id.to_string());
+            true
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.retain(|pinned| pinned != id)
+    }
+
+    // Groups go with the pin, so a later re-pin starts ungrouped.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) -> bool {
+        let before = self.order.len();
+        self.order.retain(|pinned| keep(pinned));
+
+        let order = &self.order;
+        self.groups
+            .retain(|id, _| order.iter().any(|pinned| pinned == id));
+        self.order.len() != before
+    }
+
+    pub fn rank(&self, id: &str) -> Option<(usize, usize)> {
+        let position = self.order.iter().position(|pinned| pinned == id)?;
+        Some((self.group_rank(self.group(id)), position))
+    }
+
+    fn group_rank(&self, group: Option<&str>) -> usize {
+        let Some(group) = group else {
+            return 0;
+        };
+
+        let mut seen: Vec<&str> = Vec::new();
+        for pinned in &self.order {
+            if let Some(other) = self.group(pinned) {
+                if !seen.contains(&other) {
+                    seen.push(other);
+                }
+            }
+        }
+
+        seen.iter()
+            .position(|other| *other == group)
+            .map_or(0, |index| index + 1)
+    }
+
+    fn display_order(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.order.iter().map(String::as_str).collect();
+        ids.sort_by_key(|id| self.rank(id));
+        ids
+    }
+
+    pub fn move_by(&mut self, id: &str, delta: i32) -> bool {
+        let display = self.display_order();
+        let Some(index) = display.iter().position(|pinned| *pinned == id) else {
+            return false;
+        };
+
+        let neighbor = match delta.signum() {
+            -1 => index.checked_sub(1),
+            1 => Some(index + 1),
+            _ => None,
+        };
+        let Some(neighbor) = neighbor.and_then(|n| display.get(n)).map(|n| n.to_string()) else {
+            return false;
+        };
+
+        if self.group(id) != self.group(&neighbor) {
+            return false;
+        }
+
+        let a = self.order.iter().position(|pinned| pinned == id);
+        let b = self.order.iter().position(|pinned| *pinned == neighbor);
+        if let (Some(a), Some(b)) = (a, b) {
+            self.order.swap(a, b);
+            return true;
+        }
+        false
+    }
+
+    /// Drops `id` onto `target`, taking the target's place and group.
+    pub fn move_onto(&mut self, id: &str, target: &str) -> bool {
+        if id == target || !self.contains(id) || !self.contains(target) {
+            return false;
+        }
+
+        match self.group(target).map(str::to_string) {
+            Some(group) => self.groups.insert(id.to_string(), group),
+            None => self.groups.remove(id),
+        };
+
+        let from = self.order.iter().position(|pinned| pinned == id);
+        let to = self.order.iter().position(|pinned| pinned == target);
+        let (Some(from), Some(to)) = (from, to) else {
+            return false;
+        };
+
+        let moved = self.order.remove(from);
+        self.order.insert(to, moved);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pins(order: &[&str], groups: &[(&str, &str)]) -> Pins {
+        Pins::new(
+            order.iter().map(|id| id.to_string()).collect(),
+            groups
+                .iter()
+                .map(|(id, group)| (id.to_string(), group.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn unpinning_drops_the_group() {
+        let mut pins = pins(&["a", "b"], &[("a", "Work"), ("b", "Work")]);
+
+        assert!(!pins.toggle("a"));
+        assert_eq!(pins.group("a"), None);
+        assert!(!pins.groups().contains_key("a"));
+
+        assert!(pins.toggle("a"));
+        assert_eq!(pins.group("a"), None);
+        assert_eq!(pins.group("b"), Some("Work"));
+    }
+
+    #[test]
+    fn retain_drops_groups_of_removed_pins() {
+        let mut pins = pins(&["a", "b"], &[("a", "Work"), ("b", "Games")]);
+
+        assert!(pins.retain(|id| id == "b"));
+        assert_eq!(pins.order(), ["b"]);
+        assert_eq!(pins.groups().keys().collect::<Vec<_>>(), ["b"]);
+    }
+
+    #[test]
+    fn new_ignores_groups_of_ids_that_are_not_pinned() {
+        let pins = pins(&["a"], &[("a", "Work"), ("gone", "Work")]);
+        assert_eq!(pins.groups().len(), 1);
+    }
+}